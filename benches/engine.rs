@@ -0,0 +1,127 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mandelbrot_web::color::{colorize, default_gradient};
+use mandelbrot_web::fixed::from_decimal;
+use mandelbrot_web::{
+    mandelbrot_val_at_point, process_plot_cpu, render_plot, Complex, Ctx, PlotPoint,
+    RenderStrategy, View,
+};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 180;
+
+fn view(re: &str, im: &str, span: &str, max_iters: usize) -> View {
+    View {
+        center_x: from_decimal(re).unwrap(),
+        center_y: from_decimal(im).unwrap(),
+        horizontal_span: from_decimal(span).unwrap(),
+        max_iters,
+    }
+}
+
+/// Views every benchmark is run on, from mostly escaping to never escaping points.
+fn reference_views() -> Vec<(&'static str, View)> {
+    vec![
+        ("full set", view("-0.8", "0", "3.5", 500)),
+        ("seahorse valley", view("-0.7463", "0.1102", "0.005", 1000)),
+        // A period 33 mini-brot in Seahorse Valley, about 10^7 times smaller than the set.
+        (
+            "deep mini-brot",
+            view(
+                "-0.7475603933455234",
+                "0.11065101884232985",
+                "0.0000006",
+                2000,
+            ),
+        ),
+        // Inside the main cardioid.
+        ("all interior", view("-0.2", "0.3", "0.25", 1000)),
+    ]
+}
+
+fn ctx(view: &View) -> Ctx {
+    let mut ctx = Ctx {
+        win_width: WIDTH,
+        win_height: HEIGHT,
+        chunk_size: HEIGHT,
+        ..Default::default()
+    };
+    ctx.set_view(view);
+
+    ctx
+}
+
+fn points(ctx: &Ctx) -> u64 {
+    (ctx.win_width * ctx.win_height) as u64
+}
+
+/// The bare iteration of every pixel, without any of the bookkeeping of a render.
+fn iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mandelbrot_val_at_point");
+    group.sample_size(10);
+
+    for (name, view) in reference_views() {
+        let ctx = ctx(&view);
+        let coords: Vec<Complex> = (0..ctx.win_height)
+            .flat_map(|y| (0..ctx.win_width).map(move |x| (x, y)))
+            .map(|(x, y)| Complex::new(ctx.get_x(x), ctx.get_y(y)))
+            .collect();
+        let (threshold, precision) = (ctx.threshold(), ctx.backend());
+
+        group.throughput(Throughput::Elements(points(&ctx)));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &coords, |b, coords| {
+            b.iter(|| {
+                for &c in coords {
+                    let mut p = PlotPoint::default();
+                    mandelbrot_val_at_point(c, c, ctx.max_iters, threshold, precision, &mut p);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A whole render in one chunk, with and without tracing.
+fn rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process_plot_cpu");
+    group.sample_size(10);
+
+    for (name, view) in reference_views() {
+        for strategy in [RenderStrategy::BruteForce, RenderStrategy::Tracing] {
+            let ctx = Ctx {
+                strategy,
+                ..ctx(&view)
+            };
+
+            group.throughput(Throughput::Elements(points(&ctx)));
+            group.bench_with_input(BenchmarkId::new(strategy.name(), name), &ctx, |b, ctx| {
+                b.iter(|| {
+                    let mut ctx = *ctx;
+                    let mut plot = vec![vec![PlotPoint::default(); WIDTH]; HEIGHT];
+                    process_plot_cpu(&mut ctx, &mut plot);
+
+                    plot
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Turning a computed plot into pixels, as `draw_context2d` in the worker does.
+fn coloring(c: &mut Criterion) {
+    let grad = default_gradient();
+    let mut group = c.benchmark_group("colorize");
+
+    for (name, view) in reference_views() {
+        let mut ctx = ctx(&view);
+        let plot = render_plot(&mut ctx);
+        let mut data = vec![0_u8; 4 * WIDTH * HEIGHT];
+
+        group.throughput(Throughput::Elements(points(&ctx)));
+        group.bench_function(name, |b| b.iter(|| colorize(&ctx, &grad, &plot, &mut data)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, iteration, rendering, coloring);
+criterion_main!(benches);
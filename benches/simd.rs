@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mandelbrot_web::fixed::from_decimal;
+use mandelbrot_web::simd::compute_row;
+use mandelbrot_web::{compute_point, Ctx, Precision, View};
+
+fn row_ctx(width: usize, view: View) -> Ctx {
+    let mut ctx = Ctx {
+        win_width: width,
+        win_height: 1,
+        precision: Precision::Float,
+        ..Default::default()
+    };
+    ctx.set_view(&view);
+
+    ctx
+}
+
+/// Compares the SIMD kernel with the scalar `f64` iteration on rows of the whole set and of
+/// a busier view.
+fn simd_vs_scalar(c: &mut Criterion) {
+    let views = [
+        ("whole set", View::default()),
+        (
+            "seahorse valley",
+            View {
+                center_x: from_decimal("-0.7463").unwrap(),
+                center_y: from_decimal("0.1102").unwrap(),
+                horizontal_span: from_decimal("0.005").unwrap(),
+                max_iters: 1000,
+            },
+        ),
+    ];
+
+    let mut group = c.benchmark_group("row");
+    for (name, view) in views {
+        let ctx = row_ctx(1024, view);
+        group.throughput(Throughput::Elements(ctx.win_width as u64));
+
+        group.bench_with_input(BenchmarkId::new("scalar", name), &ctx, |b, ctx| {
+            b.iter(|| {
+                (0..ctx.win_width)
+                    .map(|x| compute_point(ctx, x, 0))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("simd", name), &ctx, |b, ctx| {
+            b.iter(|| compute_row(ctx, 0))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, simd_vs_scalar);
+criterion_main!(benches);
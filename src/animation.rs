@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::video::{zoom_view, IterRamp};
+use crate::{Complex, Ctx, View};
+
+/// How the parameters move from one keyframe to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::EaseIn => "Ease in",
+            Easing::EaseOut => "Ease out",
+            Easing::EaseInOut => "Ease in-out",
+        }
+    }
+
+    /// Maps the linear progress `t` (from 0 to 1) to the eased one.
+    pub fn apply(&self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Seconds from the start of the animation.
+    pub time: f64,
+    pub view: View,
+    pub palette_offset: f64,
+    pub brightness: f64,
+    pub julia: Option<Complex>,
+    /// Easing of the way to the next keyframe.
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    pub fn from_ctx(ctx: &Ctx, time: f64) -> Self {
+        Self {
+            time,
+            view: ctx.view(),
+            palette_offset: ctx.palette_offset,
+            brightness: ctx.brightness,
+            julia: ctx.julia,
+            easing: Default::default(),
+        }
+    }
+
+    pub fn apply_to(&self, ctx: &mut Ctx) {
+        ctx.set_view(&self.view);
+        ctx.palette_offset = self.palette_offset;
+        ctx.brightness = self.brightness;
+        ctx.julia = self.julia;
+    }
+
+    /// Returns the keyframe at the eased fraction `t` of the way from `self` to `next`.
+    fn interpolate(&self, next: &Keyframe, t: f64) -> Keyframe {
+        let t = self.easing.apply(t);
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        let lerp_i64 = |a: i64, b: i64| a + ((b as i128 - a as i128) as f64 * t) as i64;
+
+        Keyframe {
+            time: lerp(self.time, next.time),
+            view: zoom_view(&self.view, &next.view, t, IterRamp::Depth),
+            palette_offset: lerp(self.palette_offset, next.palette_offset),
+            brightness: lerp(self.brightness, next.brightness),
+            julia: match (self.julia, next.julia) {
+                (Some(a), Some(b)) => {
+                    Some(Complex::new(lerp_i64(a.re, b.re), lerp_i64(a.im, b.im)))
+                }
+                (julia, _) => julia,
+            },
+            easing: self.easing,
+        }
+    }
+}
+
+/// Keyframes ordered by time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timeline {
+    keyframes: Vec<Keyframe>,
+}
+
+impl Timeline {
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn add(&mut self, keyframe: Keyframe) {
+        let i = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(i, keyframe);
+    }
+
+    pub fn remove(&mut self, i: usize) -> Keyframe {
+        self.keyframes.remove(i)
+    }
+
+    /// Replaces the keyframe at `i`, keeping the timeline ordered.
+    pub fn update(&mut self, i: usize, keyframe: Keyframe) {
+        self.keyframes.remove(i);
+        self.add(keyframe);
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map(|k| k.time).unwrap_or_default()
+    }
+
+    /// Returns the state of the animation at `time` seconds.
+    pub fn sample(&self, time: f64) -> Option<Keyframe> {
+        let first = self.keyframes.first()?;
+        if time <= first.time {
+            return Some(*first);
+        }
+
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == self.keyframes.len() {
+            return self.keyframes.last().copied();
+        }
+
+        let (prev, next) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        Some(prev.interpolate(next, (time - prev.time) / (next.time - prev.time)))
+    }
+
+    /// Returns the contexts of all frames of the animation played at `fps`, based on `ctx`.
+    pub fn frames(&self, ctx: &Ctx, fps: f64) -> Vec<Ctx> {
+        if self.keyframes.is_empty() || fps <= 0.0 {
+            return Vec::new();
+        }
+
+        let start = self.keyframes[0].time;
+        let count = ((self.duration() - start) * fps).floor() as usize + 1;
+
+        (0..count)
+            .filter_map(|i| self.sample(start + i as f64 / fps))
+            .map(|keyframe| {
+                let mut frame_ctx = *ctx;
+                keyframe.apply_to(&mut frame_ctx);
+                frame_ctx
+            })
+            .collect()
+    }
+}
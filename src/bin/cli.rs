@@ -0,0 +1,309 @@
+use mandelbrot_web::bookmarks::gallery;
+use mandelbrot_web::color::{default_gradient, render_rgba};
+use mandelbrot_web::export::{encode_png, import_png, is_png};
+use mandelbrot_web::iterations::estimate_iters;
+#[cfg(feature = "parallel")]
+use mandelbrot_web::parallel::render_plot_parallel;
+use mandelbrot_web::params::ParamsFormat;
+use mandelbrot_web::poster::render_poster;
+use mandelbrot_web::video::{write_y4m_frame, write_y4m_header, zoom_path, IterRamp};
+use mandelbrot_web::{render_plot, Ctx, Precision, RenderStrategy, View};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+const USAGE: &str = "Usage: cli poster [options]
+       cli zoom [options]
+       cli compare [options]   times every render strategy against brute force,
+                           and in parallel if built with the parallel feature
+       cli gallery [options]   writes thumbnails of the built-in gallery to --out-dir
+       cli params [options]    writes the view to a .json, .kfr or .xpf file given by --out
+
+View options (coordinates are Q4.60 fixed point):
+  --center-x <i64>    --center-y <i64>    --span <i64>
+  --iters <usize>     --brightness <f64>
+  --strategy <brute-force|tracing|subdivision>
+  --chunk-size <usize>    --bailout <f64>    --symmetry <bool>
+  --precision <auto|float|fixed|arbitrary>
+  --params <path>     reads the view from a .json, .kfr or .xpf file, or a PNG saved
+                      by this program
+
+Output options:
+  --width <usize>     --height <usize>    --out <path>
+
+Zoom options (the view options above describe the start view):
+  --end-center-x <i64>    --end-center-y <i64>    --end-span <i64>
+  --end-iters <usize>     --frames <usize>        --ramp <constant|depth>
+  --out-dir <path>        writes numbered PNG frames
+  --y4m <path>            writes an uncompressed YUV4MPEG2 stream instead
+  --fps <usize>
+  --end-iters defaults to the limit estimated for the depth of the end view";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("poster") => parse_options(&args[1..]).and_then(|opts| poster(&opts)),
+        Some("zoom") => parse_options(&args[1..]).and_then(|opts| zoom(&opts)),
+        Some("compare") => parse_options(&args[1..]).and_then(|opts| compare(&opts)),
+        Some("gallery") => parse_options(&args[1..]).and_then(|opts| write_gallery(&opts)),
+        Some("params") => parse_options(&args[1..]).and_then(|opts| write_params(&opts)),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn poster(opts: &HashMap<String, String>) -> Result<(), String> {
+    let ctx = ctx_from_options(opts)?;
+    let out: String = get(opts, "out", "poster.png".to_string())?;
+
+    let file = File::create(&out).map_err(|e| format!("Failed to create {out}: {e}"))?;
+
+    render_poster(&ctx, &default_gradient(), BufWriter::new(file), |rows| {
+        eprint!("\r{rows}/{} rows", ctx.win_height)
+    })
+    .map_err(|e| format!("Failed to write {out}: {e}"))?;
+
+    eprintln!();
+
+    Ok(())
+}
+
+fn zoom(opts: &HashMap<String, String>) -> Result<(), String> {
+    let ctx = ctx_from_options(opts)?;
+    let start = ctx.view();
+    let mut end = View {
+        center_x: get(opts, "end-center-x", start.center_x)?,
+        center_y: get(opts, "end-center-y", start.center_y)?,
+        horizontal_span: get(opts, "end-span", start.horizontal_span)?,
+        max_iters: start.max_iters,
+    };
+    end.max_iters = get(opts, "end-iters", estimate_iters(&end).max(start.max_iters))?;
+    let frames: usize = get(opts, "frames", 100)?;
+    let ramp = match get(opts, "ramp", "depth".to_string())?.as_str() {
+        "constant" => IterRamp::Constant,
+        "depth" => IterRamp::Depth,
+        other => return Err(format!("Invalid value {other} for --ramp.")),
+    };
+
+    if end.horizontal_span <= 0 {
+        return Err("The end span must be positive.".to_string());
+    }
+
+    let grad = default_gradient();
+    let views = zoom_path(&start, &end, frames, ramp);
+
+    let mut y4m = match opts.get("y4m") {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| format!("Failed to create {path}: {e}"))?;
+            let mut w = BufWriter::new(file);
+            write_y4m_header(&mut w, ctx.win_width, ctx.win_height, get(opts, "fps", 30)?)
+                .map_err(|e| format!("Failed to write {path}: {e}"))?;
+            Some(w)
+        }
+        None => None,
+    };
+    let out_dir: String = get(opts, "out-dir", "frames".to_string())?;
+    if y4m.is_none() {
+        std::fs::create_dir_all(&out_dir)
+            .map_err(|e| format!("Failed to create {out_dir}: {e}"))?;
+    }
+
+    for (i, view) in views.iter().enumerate() {
+        let mut frame_ctx = ctx;
+        frame_ctx.set_view(view);
+        let data = render_rgba(&mut frame_ctx, &grad);
+
+        match y4m.as_mut() {
+            Some(w) => {
+                write_y4m_frame(w, &data).map_err(|e| format!("Failed to write frame: {e}"))?
+            }
+            None => {
+                let path = Path::new(&out_dir).join(format!("frame_{i:05}.png"));
+                let png = encode_png(&frame_ctx, &data).map_err(|e| e.to_string())?;
+                std::fs::write(&path, png)
+                    .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            }
+        }
+
+        eprint!("\r{}/{frames} frames", i + 1);
+    }
+
+    if let Some(mut w) = y4m {
+        w.flush()
+            .map_err(|e| format!("Failed to write frame: {e}"))?;
+    }
+
+    eprintln!();
+
+    Ok(())
+}
+
+fn write_gallery(opts: &HashMap<String, String>) -> Result<(), String> {
+    let ctx = ctx_from_options(opts)?;
+    let out_dir: String = get(opts, "out-dir", "gallery".to_string())?;
+    std::fs::create_dir_all(&out_dir).map_err(|e| format!("Failed to create {out_dir}: {e}"))?;
+
+    let grad = default_gradient();
+
+    for bookmark in gallery() {
+        let data = bookmark.thumbnail(&ctx, ctx.win_width, ctx.win_height, &grad)?;
+
+        let mut thumbnail_ctx = ctx;
+        bookmark.apply_to(&mut thumbnail_ctx);
+        let png = encode_png(&thumbnail_ctx, &data).map_err(|e| e.to_string())?;
+
+        let file_name = bookmark.name.to_lowercase().replace(' ', "_") + ".png";
+        let path = Path::new(&out_dir).join(file_name);
+        std::fs::write(&path, png)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+        eprintln!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+fn write_params(opts: &HashMap<String, String>) -> Result<(), String> {
+    let ctx = ctx_from_options(opts)?;
+    let out: String = get(opts, "out", "view.json".to_string())?;
+    let format = params_format(&out)?;
+
+    std::fs::write(&out, format.export(&ctx)).map_err(|e| format!("Failed to write {out}: {e}"))
+}
+
+fn compare(opts: &HashMap<String, String>) -> Result<(), String> {
+    let ctx = ctx_from_options(opts)?;
+    let mut reference = None;
+
+    for strategy in RenderStrategy::ALL {
+        let mut strategy_ctx = Ctx { strategy, ..ctx };
+
+        let start = Instant::now();
+        let plot = render_plot(&mut strategy_ctx);
+        let elapsed = start.elapsed();
+
+        let reference = reference.get_or_insert_with(|| (plot.clone(), elapsed));
+        let points = plot.iter().flatten();
+        let computed = points.clone().filter(|p| !p.filled).count();
+        let differing = points
+            .zip(reference.0.iter().flatten())
+            .filter(|(p, r)| !p.same_as(r))
+            .count();
+
+        println!(
+            "{strategy:?}: {:.1} ms, {:.2}x speedup, {computed} points computed, {differing} pixels differ",
+            elapsed.as_secs_f64() * 1000.0,
+            reference.1.as_secs_f64() / elapsed.as_secs_f64(),
+        );
+
+        #[cfg(feature = "parallel")]
+        {
+            let start = Instant::now();
+            render_plot_parallel(&mut Ctx { strategy, ..ctx });
+            println!(
+                "{strategy:?} in parallel, in chunks of {} rows: {:.1} ms",
+                ctx.chunk_size,
+                start.elapsed().as_secs_f64() * 1000.0,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn ctx_from_options(opts: &HashMap<String, String>) -> Result<Ctx, String> {
+    let default_view = View::default();
+
+    let mut ctx = Ctx {
+        win_width: get(opts, "width", 1920)?,
+        win_height: get(opts, "height", 1080)?,
+        brightness: get(opts, "brightness", Ctx::default().brightness)?,
+        strategy: name_option(opts, "strategy", RenderStrategy::from_name)?,
+        chunk_size: get(opts, "chunk-size", Ctx::default().chunk_size)?,
+        bailout: get(opts, "bailout", Ctx::default().bailout)?,
+        symmetry: get(opts, "symmetry", Ctx::default().symmetry)?,
+        precision: name_option(opts, "precision", Precision::from_name)?,
+        ..Default::default()
+    };
+
+    if ctx.win_width == 0 || ctx.win_height == 0 {
+        return Err("Image dimensions must not be zero.".to_string());
+    }
+
+    ctx.set_view(&View {
+        center_x: get(opts, "center-x", default_view.center_x)?,
+        center_y: get(opts, "center-y", default_view.center_y)?,
+        horizontal_span: get(opts, "span", default_view.horizontal_span)?,
+        max_iters: get(opts, "iters", default_view.max_iters)?,
+    });
+
+    if let Some(path) = opts.get("params") {
+        let result = if is_png(path) {
+            let png = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            import_png(&png, &mut ctx)
+        } else {
+            let text =
+                std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            params_format(path)?.import(&text, &mut ctx)
+        };
+        result.map_err(|e| format!("Failed to read {path}: {e}"))?;
+    }
+    ctx.validate()?;
+
+    Ok(ctx)
+}
+
+fn params_format(path: &str) -> Result<ParamsFormat, String> {
+    ParamsFormat::from_file_name(path)
+        .ok_or_else(|| format!("Unknown parameter file format of {path}."))
+}
+
+fn parse_options(args: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut opts = HashMap::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let key = arg
+            .strip_prefix("--")
+            .ok_or_else(|| format!("Unexpected argument {arg}.\n\n{USAGE}"))?;
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for --{key}.\n\n{USAGE}"))?;
+
+        opts.insert(key.to_string(), value.clone());
+    }
+
+    Ok(opts)
+}
+
+/// Parses an option whose values are names, defaulting to the `Default` of `T`.
+fn name_option<T: Default>(
+    opts: &HashMap<String, String>,
+    key: &str,
+    from_name: impl Fn(&str) -> Option<T>,
+) -> Result<T, String> {
+    match opts.get(key) {
+        Some(value) => {
+            from_name(value).ok_or_else(|| format!("Invalid value {value} for --{key}."))
+        }
+        None => Ok(T::default()),
+    }
+}
+
+fn get<T: FromStr>(opts: &HashMap<String, String>, key: &str, default: T) -> Result<T, String> {
+    match opts.get(key) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("Invalid value {value} for --{key}.")),
+        None => Ok(default),
+    }
+}
@@ -1,29 +1,22 @@
-use mandelbrot_web::{process_plot_cpu, Ctx, PlotPoint, Symmetry};
+use mandelbrot_web::cache::PlotCache;
+use mandelbrot_web::color::{colorize, default_gradient, render_rgba};
+use mandelbrot_web::export::{encode_png, finish_tar, write_tar_entry};
+use mandelbrot_web::iterations::{change_iters, estimate_iters, needs_more_iters, raise_iters};
+use mandelbrot_web::poster::render_poster;
+use mandelbrot_web::{process_plot_progressive, Ctx, PlotPoint};
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::RwLock;
 use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::js_sys::{Array, Uint8ClampedArray, WebAssembly};
+use web_sys::js_sys::{Array, Uint8Array, Uint8ClampedArray, WebAssembly};
 use web_sys::{DedicatedWorkerGlobalScope, MessageEvent};
 
 static GRAD: RwLock<Option<colorgrad::Gradient>> = RwLock::new(None);
 
-fn get_grad() -> colorgrad::Gradient {
-    colorgrad::CustomGradient::new()
-        .colors(&[
-            colorgrad::Color::from_rgba8(0, 0, 0, 255),
-            colorgrad::Color::from_rgba8(0, 0, 145, 255),
-            colorgrad::Color::from_rgba8(145, 0, 255, 255),
-            colorgrad::Color::from_rgba8(255, 0, 0, 255),
-            colorgrad::Color::from_rgba8(255, 255, 0, 255),
-            colorgrad::Color::from_rgba8(255, 255, 255, 255),
-        ])
-        .mode(colorgrad::BlendMode::Oklab)
-        .interpolation(colorgrad::Interpolation::CatmullRom)
-        .build()
-        .unwrap()
-}
+/// Incremented with every new render, so that frames scheduled by an outdated one are dropped.
+static GENERATION: AtomicU32 = AtomicU32::new(0);
 
 fn main() {
     console_error_panic_hook::set_once();
@@ -31,21 +24,116 @@ fn main() {
 
     let scope = DedicatedWorkerGlobalScope::from(JsValue::from(web_sys::js_sys::global()));
 
+    let canvas_ref = Rc::new(RefCell::new(None));
     let context_ref = Rc::new(RefCell::new(None));
     let plot_ref = Rc::new(RefCell::new(None));
     let rgb_data_ref = Rc::new(RefCell::new(None));
+    // The context the current plot was computed for.
+    let rendered_ref = Rc::new(RefCell::new(None::<Ctx>));
+    let cache_ref = Rc::new(RefCell::new(PlotCache::default()));
 
     let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |msg: MessageEvent| {
         let context_ref = context_ref.clone();
 
-        *GRAD.write().unwrap() = Some(get_grad());
+        *GRAD.write().unwrap() = Some(default_gradient());
 
         let data = Array::from(&msg.data());
         let first_param = data.get(0);
+
+        if first_param.as_string().as_deref() == Some("export") {
+            let mut ctx: Ctx = serde_wasm_bindgen::from_value(data.get(1)).unwrap();
+
+            let data = render_rgba(&mut ctx, GRAD.read().unwrap().as_ref().unwrap());
+            let png = encode_png(&ctx, &data).unwrap();
+
+            let msg = Array::new();
+            msg.push(&"export".into());
+            msg.push(&Uint8Array::from(&png[..]));
+
+            let scope = DedicatedWorkerGlobalScope::from(JsValue::from(web_sys::js_sys::global()));
+            scope.post_message(&msg).unwrap();
+
+            return;
+        }
+
+        if first_param.as_string().as_deref() == Some("poster") {
+            let ctx: Ctx = serde_wasm_bindgen::from_value(data.get(1)).unwrap();
+            let scope = DedicatedWorkerGlobalScope::from(JsValue::from(web_sys::js_sys::global()));
+
+            let mut png = Vec::new();
+            let grad = GRAD.read().unwrap();
+            render_poster(&ctx, grad.as_ref().unwrap(), &mut png, |rows| {
+                let msg = Array::new();
+                msg.push(&"progress".into());
+                msg.push(&(rows as f64 / ctx.win_height as f64).into());
+                scope.post_message(&msg).unwrap();
+            })
+            .unwrap();
+
+            let msg = Array::new();
+            msg.push(&"poster".into());
+            msg.push(&Uint8Array::from(&png[..]));
+            scope.post_message(&msg).unwrap();
+
+            return;
+        }
+
+        if first_param.as_string().as_deref() == Some("frames") {
+            let frames = Array::from(&data.get(1));
+            let raw = data.get(2).is_truthy();
+            let scope = DedicatedWorkerGlobalScope::from(JsValue::from(web_sys::js_sys::global()));
+
+            // Without `raw`, the frames are sent back at the end as one archive of PNG files.
+            let mut archive = Vec::new();
+
+            for (i, frame) in frames.iter().enumerate() {
+                let mut frame_ctx: Ctx = serde_wasm_bindgen::from_value(frame).unwrap();
+                let data = render_rgba(&mut frame_ctx, GRAD.read().unwrap().as_ref().unwrap());
+
+                if raw {
+                    let msg = Array::new();
+                    msg.push(&"frame".into());
+                    msg.push(&(i as f64).into());
+                    msg.push(&Uint8Array::from(&data[..]));
+                    scope.post_message(&msg).unwrap();
+                } else {
+                    let png = encode_png(&frame_ctx, &data).unwrap();
+                    write_tar_entry(&mut archive, &format!("frame_{i:05}.png"), &png).unwrap();
+                }
+
+                let msg = Array::new();
+                msg.push(&"progress".into());
+                msg.push(&((i + 1) as f64 / frames.length() as f64).into());
+                scope.post_message(&msg).unwrap();
+            }
+
+            let msg = Array::new();
+            msg.push(&"done".into());
+            if !raw {
+                finish_tar(&mut archive).unwrap();
+                msg.push(&Uint8Array::from(&archive[..]));
+            }
+            scope.post_message(&msg).unwrap();
+
+            return;
+        }
+
         let mut ctx: Ctx = if first_param.is_instance_of::<OffscreenCanvas>() {
             let canvas = first_param.dyn_into::<OffscreenCanvas>().unwrap();
             *context_ref.borrow_mut() = Some(canvas.get_context("2d").unwrap().unwrap());
+            *canvas_ref.borrow_mut() = Some(canvas);
             serde_wasm_bindgen::from_value(data.get(1)).unwrap()
+        } else if first_param.as_string().as_deref() == Some("resize") {
+            let ctx: Ctx = serde_wasm_bindgen::from_value(data.get(1)).unwrap();
+
+            if let Some(canvas) = canvas_ref.borrow().as_ref() {
+                canvas.set_width(ctx.win_width as u32);
+                canvas.set_height(ctx.win_height as u32);
+            }
+            // The plot is reallocated for the new size below.
+            *plot_ref.borrow_mut() = None;
+
+            ctx
         } else {
             serde_wasm_bindgen::from_value(first_param).unwrap()
         };
@@ -53,6 +141,12 @@ fn main() {
         web_sys::console::log_1(&format!("Plotting initiated. {:?}", ctx).into());
 
         let plot_initialized = plot_ref.borrow_mut().as_ref().is_some();
+        // Only the iteration limit changed, the points computed so far can be kept.
+        let iters_changed = plot_initialized
+            && !ctx.auto_iters
+            && rendered_ref
+                .borrow()
+                .is_some_and(|r: Ctx| r.same_points(&ctx) && r.max_iters != ctx.max_iters);
 
         if !plot_initialized {
             web_sys::console::log_1(&"Allocating plot data".into());
@@ -60,27 +154,44 @@ fn main() {
                 vec![PlotPoint::default(); ctx.win_width];
                 ctx.win_height
             ]);
-        } else if ctx.needs_recalc {
-            plot_ref
-                .borrow_mut()
-                .as_mut()
-                .unwrap()
-                .iter_mut()
+        } else if ctx.needs_recalc && !iters_changed {
+            let mut plot = plot_ref.borrow_mut();
+            let plot = plot.as_mut().unwrap();
+
+            // Limits raised automatically leave points classified with different limits.
+            if let Some(rendered) = rendered_ref.borrow().filter(|r| !r.auto_iters) {
+                cache_ref.borrow_mut().store(&rendered, plot);
+            }
+
+            plot.iter_mut()
                 .for_each(|row| row.iter_mut().for_each(|val| val.reset()))
         }
 
         if ctx.needs_recalc {
-            *rgb_data_ref.borrow_mut() = Some(vec![0 as u8; 4 * ctx.win_width * ctx.win_height]);
+            if iters_changed {
+                change_iters(&mut ctx, plot_ref.borrow_mut().as_mut().unwrap());
+            } else {
+                *rgb_data_ref.borrow_mut() = Some(vec![0_u8; 4 * ctx.win_width * ctx.win_height]);
+                ctx.start_render();
+                if ctx.auto_iters {
+                    ctx.max_iters = estimate_iters(&ctx.view());
+                }
+                cache_ref
+                    .borrow()
+                    .fill(&mut ctx, plot_ref.borrow_mut().as_mut().unwrap());
+            }
+            *rendered_ref.borrow_mut() = Some(ctx);
 
-            let _ = draw_plot_cpu(
+            draw_plot_cpu(
                 &mut ctx,
+                GENERATION.fetch_add(1, Ordering::Relaxed) + 1,
                 context_ref.clone(),
                 plot_ref.clone(),
                 rgb_data_ref.clone(),
             );
         } else {
             draw_context2d(
-                &mut ctx,
+                &ctx,
                 context_ref.clone(),
                 plot_ref.clone(),
                 rgb_data_ref.clone(),
@@ -97,74 +208,43 @@ fn main() {
 
 fn draw_plot_cpu(
     ctx: &mut Ctx,
+    generation: u32,
     context_ref: Rc<RefCell<Option<OffscreenCanvasRenderingContext2d>>>,
     plot_ref: Rc<RefCell<Option<Vec<Vec<PlotPoint>>>>>,
     data_ref: Rc<RefCell<Option<Vec<u8>>>>,
 ) {
+    if GENERATION.load(Ordering::Relaxed) != generation {
+        return;
+    }
+
     {
         let mut plot = plot_ref.borrow_mut();
         let plot: &mut Vec<Vec<PlotPoint>> = plot.as_mut().unwrap();
 
-        let (y0, symmetry) = ctx.revert_y(0);
-        let symmetry_shift = match symmetry {
-            Symmetry::Exact => 0,
-            Symmetry::OverOne => 1,
-            Symmetry::OverTwo => 2,
-        };
-        let y_max = plot.len() - 1;
-        let y1 = y0.min(y_max);
-        let y2 = y1 + 1;
-        let y3 = if y1 > 0 {
-            (y1 * 2 + 1 - symmetry_shift).min(y_max)
-        } else {
-            0
-        };
-        let y4 = if y3 > 0 { y3 + 1 } else { 0 };
-
-        let mut rows_processed: usize = 0;
+        let rows_processed = process_plot_progressive(ctx, plot);
 
-        if ctx.rows_processed == 0 {
-            rows_processed += y3 - y1;
-        }
-
-        if y1 > 0 {
-            ctx.offset = 0;
-            rows_processed += process_plot_cpu(ctx, &mut plot[0..=y1]);
-        }
-
-        if y3 > y2 {
-            for (i, y) in (y2..=y3).enumerate() {
-                for x in 0..ctx.win_width {
-                    plot[y][x] = plot[y1 - i - symmetry_shift][x];
-                }
-            }
-        }
+        ctx.next_chunk();
+        ctx.total_rows_processed += rows_processed;
 
-        if y_max >= y4 {
-            ctx.offset = y4;
-            rows_processed += process_plot_cpu(ctx, &mut plot[y4..=y_max]);
+        if ctx.total_rows_processed >= ctx.win_height
+            && ctx.auto_iters
+            && needs_more_iters(ctx, plot)
+        {
+            raise_iters(ctx, plot);
         }
-
-        ctx.rows_processed += ctx.chunk_size;
-        ctx.total_rows_processed += rows_processed;
     }
 
-    let mut ctx_cl = ctx.clone();
-    draw_context2d(
-        &mut ctx_cl,
-        context_ref.clone(),
-        plot_ref.clone(),
-        data_ref.clone(),
-    );
+    draw_context2d(ctx, context_ref.clone(), plot_ref.clone(), data_ref.clone());
 
     let scope = DedicatedWorkerGlobalScope::from(JsValue::from(web_sys::js_sys::global()));
 
     if ctx.total_rows_processed < ctx.win_height {
-        let mut ctx_cl = ctx.clone();
+        let mut ctx_cl = *ctx;
 
         let cl = Closure::<dyn FnMut()>::new(move || {
             draw_plot_cpu(
                 &mut ctx_cl,
+                generation,
                 context_ref.clone(),
                 plot_ref.clone(),
                 data_ref.clone(),
@@ -202,27 +282,7 @@ fn draw_context2d(
     let grad = GRAD.read().unwrap();
     let grad = grad.as_ref().unwrap();
 
-    let coef: f64 = (1.0 - ctx.brightness).powi(10);
-    let coef_ln = coef.ln();
-
-    for (cur_y, row) in plot.iter().enumerate() {
-        for (cur_x, val) in row.iter().enumerate() {
-            if !val.processed() {
-                continue;
-            }
-
-            let color = if val.stable() {
-                grad.at(0.0).to_rgba8()
-            } else {
-                let rel_val = (val.calculated_value() as i128 - ctx.min_value) as f64
-                    / (ctx.max_value - ctx.min_value) as f64;
-                let rel_val: f64 = (((rel_val.powi(2) + coef) as f64).ln() - coef_ln) / ((rel_val + coef).ln() - coef_ln);
-                grad.at(rel_val).to_rgba8()
-            };
-
-            set_pixel(ctx, data, cur_x, cur_y, color[0], color[1], color[2]);
-        }
-    }
+    colorize(ctx, grad, plot, data);
 
     let base = data.as_ptr() as usize;
     let len = data.len();
@@ -231,15 +291,6 @@ fn draw_context2d(
     context.put_image_data(&img, 0.0, 0.0).unwrap();
 }
 
-fn set_pixel(ctx: &Ctx, data: &mut Vec<u8>, x: usize, y: usize, r: u8, g: u8, b: u8) {
-    let index = (x + y * ctx.win_width) * 4;
-
-    data[index + 0] = r;
-    data[index + 1] = g;
-    data[index + 2] = b;
-    data[index + 3] = 255;
-}
-
 #[wasm_bindgen]
 extern "C" {
     pub type OffscreenCanvas;
@@ -257,6 +308,12 @@ extern "C" {
         dy: f64,
     ) -> Result<(), JsValue>;
 
+    #[wasm_bindgen(method, structural, js_class = "OffscreenCanvas", setter = width)]
+    pub fn set_width(this: &OffscreenCanvas, value: u32);
+
+    #[wasm_bindgen(method, structural, js_class = "OffscreenCanvas", setter = height)]
+    pub fn set_height(this: &OffscreenCanvas, value: u32);
+
     #[wasm_bindgen(catch , method , structural , js_class = "OffscreenCanvas" , js_name = getContext)]
     pub fn get_context(
         this: &OffscreenCanvas,
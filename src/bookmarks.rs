@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::color::render_rgba;
+use crate::fixed::from_decimal;
+use crate::{Complex, Ctx, View};
+
+/// A saved view, with the palette it was looked at with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub view: View,
+    pub palette_offset: f64,
+    pub brightness: f64,
+    pub julia: Option<Complex>,
+}
+
+impl Bookmark {
+    pub fn from_ctx(name: &str, ctx: &Ctx) -> Self {
+        Self {
+            name: name.to_string(),
+            view: ctx.view(),
+            palette_offset: ctx.palette_offset,
+            brightness: ctx.brightness,
+            julia: ctx.julia,
+        }
+    }
+
+    pub fn apply_to(&self, ctx: &mut Ctx) {
+        ctx.set_view(&self.view);
+        ctx.palette_offset = self.palette_offset;
+        ctx.brightness = self.brightness;
+        ctx.julia = self.julia;
+    }
+
+    /// Renders the bookmarked view into an RGBA buffer, based on the settings of `ctx`.
+    pub fn thumbnail(
+        &self,
+        ctx: &Ctx,
+        width: usize,
+        height: usize,
+        grad: &colorgrad::Gradient,
+    ) -> Result<Vec<u8>, String> {
+        let mut thumbnail_ctx = ctx.resized(width, height)?;
+        self.apply_to(&mut thumbnail_ctx);
+
+        Ok(render_rgba(&mut thumbnail_ctx, grad))
+    }
+}
+
+/// Bookmarks ordered by the time they were saved, with unique names.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bookmarks {
+    bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// Adds `bookmark`, replacing the one with the same name, if any.
+    pub fn save(&mut self, bookmark: Bookmark) {
+        match self.bookmarks.iter_mut().find(|b| b.name == bookmark.name) {
+            Some(existing) => *existing = bookmark,
+            None => self.bookmarks.push(bookmark),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Bookmark> {
+        let i = self.bookmarks.iter().position(|b| b.name == name)?;
+        Some(self.bookmarks.remove(i))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Bookmarks should always serialize.")
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+/// Entry of `gallery.json`, with the coordinates as decimals so that they are easy to edit.
+#[derive(Deserialize)]
+struct GalleryEntry {
+    name: String,
+    re: String,
+    im: String,
+    span: String,
+    max_iters: usize,
+}
+
+/// Famous locations shipped with the crate.
+pub fn gallery() -> Vec<Bookmark> {
+    let entries: Vec<GalleryEntry> = serde_json::from_str(include_str!("gallery.json"))
+        .expect("The gallery should be valid JSON.");
+    let default = Ctx::default();
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let coord = |s: &str| {
+                from_decimal(s)
+                    .unwrap_or_else(|| panic!("Invalid coordinate {s} of {}.", entry.name))
+            };
+
+            Bookmark {
+                view: View {
+                    center_x: coord(&entry.re),
+                    center_y: coord(&entry.im),
+                    horizontal_span: coord(&entry.span),
+                    max_iters: entry.max_iters,
+                },
+                name: entry.name,
+                palette_offset: default.palette_offset,
+                brightness: default.brightness,
+                julia: None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,110 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Complex, Ctx, PlotPoint, Precision};
+
+/// Most points the cache holds before it starts over.
+pub const MAX_CACHED_POINTS: usize = 1 << 21;
+
+/// Points computed by earlier renders, keyed by their coordinates.
+///
+/// After an exact integer zoom or an integer pixel pan, many pixels of the new view land on
+/// points computed before. [`PlotCache::fill`] puts those into the new plot, and
+/// [`crate::process_plot_cpu`] skips them like any other processed point.
+#[derive(Default)]
+pub struct PlotCache {
+    points: HashMap<(i64, i64), PlotPoint>,
+    // Coordinates of cached columns and rows, so that views off the cached grid cost one
+    // lookup per column and row rather than per pixel.
+    xs: HashSet<i64>,
+    ys: HashSet<i64>,
+    max_iters: usize,
+    julia: Option<Complex>,
+    bailout: f64,
+    precision: Precision,
+}
+
+impl PlotCache {
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.xs.clear();
+        self.ys.clear();
+    }
+
+    /// Whether the cached points were computed with the same formula and limits as `ctx` uses.
+    fn matches(&self, ctx: &Ctx) -> bool {
+        self.max_iters == ctx.max_iters
+            && self.julia == ctx.julia
+            && self.bailout == ctx.bailout
+            && self.precision == ctx.backend()
+    }
+
+    /// Adds the computed points of `plot`, which covers the view of `ctx`. Filled points are
+    /// left out, as their value is only inferred from their neighbours. Of a plot larger than
+    /// the cache, only the first [`MAX_CACHED_POINTS`] points are kept.
+    pub fn store(&mut self, ctx: &Ctx, plot: &[Vec<PlotPoint>]) {
+        if !self.matches(ctx) {
+            self.clear();
+            self.max_iters = ctx.max_iters;
+            self.julia = ctx.julia;
+            self.bailout = ctx.bailout;
+            self.precision = ctx.backend();
+        }
+
+        if self.points.len() + ctx.win_width * ctx.win_height > MAX_CACHED_POINTS {
+            self.clear();
+        }
+
+        for (y, row) in plot.iter().enumerate() {
+            for (x, point) in row.iter().enumerate() {
+                if self.points.len() == MAX_CACHED_POINTS {
+                    return;
+                }
+                if point.processed() && !point.filled {
+                    let coords = ctx.get_coords(x, y);
+
+                    self.points.insert(coords, *point);
+                    self.xs.insert(coords.0);
+                    self.ys.insert(coords.1);
+                }
+            }
+        }
+    }
+
+    /// Puts the cached points that lie on pixels of `ctx` into `plot`, updating the value
+    /// range of `ctx`. Returns the number of points reused.
+    pub fn fill(&self, ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) -> usize {
+        if self.points.is_empty() || !self.matches(ctx) {
+            return 0;
+        }
+
+        let columns: Vec<usize> = (0..ctx.win_width)
+            .filter(|&x| self.xs.contains(&ctx.get_x(x)))
+            .collect();
+        let mut reused = 0;
+
+        for (y, row) in plot.iter_mut().enumerate() {
+            let cy = ctx.get_y(y);
+            if !self.ys.contains(&cy) {
+                continue;
+            }
+
+            for &x in &columns {
+                if let Some(point) = self.points.get(&(ctx.get_x(x), cy)) {
+                    row[x] = *point;
+                    ctx.include_value(point.calculated_value());
+                    reused += 1;
+                }
+            }
+        }
+
+        reused
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{render_plot, Ctx, PlotPoint, PREVIEW_STEP};
+
+pub fn default_gradient() -> colorgrad::Gradient {
+    colorgrad::CustomGradient::new()
+        .colors(&[
+            colorgrad::Color::from_rgba8(0, 0, 0, 255),
+            colorgrad::Color::from_rgba8(0, 0, 145, 255),
+            colorgrad::Color::from_rgba8(145, 0, 255, 255),
+            colorgrad::Color::from_rgba8(255, 0, 0, 255),
+            colorgrad::Color::from_rgba8(255, 255, 0, 255),
+            colorgrad::Color::from_rgba8(255, 255, 255, 255),
+        ])
+        .mode(colorgrad::BlendMode::Oklab)
+        .interpolation(colorgrad::Interpolation::CatmullRom)
+        .build()
+        .unwrap()
+}
+
+/// Writes the colors of all processed points of `plot` into the RGBA buffer `data`.
+///
+/// Points not processed yet take the color of the closest point above and to the left of them
+/// known from a coarse pass, if any.
+pub fn colorize(ctx: &Ctx, grad: &colorgrad::Gradient, plot: &[Vec<PlotPoint>], data: &mut [u8]) {
+    let coef: f64 = (1.0 - ctx.brightness).powi(10);
+    let coef_ln = coef.ln();
+
+    for (cur_y, row) in plot.iter().enumerate() {
+        for (cur_x, val) in row.iter().enumerate() {
+            let val = if val.processed() {
+                val
+            } else {
+                let coarse = std::iter::successors(Some(2), |step| Some(step * 2))
+                    .take_while(|&step| step <= PREVIEW_STEP)
+                    .map(|step| &plot[cur_y - cur_y % step][cur_x - cur_x % step])
+                    .find(|val| val.processed());
+
+                match coarse {
+                    Some(val) => val,
+                    None => continue,
+                }
+            };
+
+            let color = if val.stable() {
+                grad.at(0.0).to_rgba8()
+            } else {
+                let rel_val = (val.calculated_value() - ctx.min_value) as f64
+                    / (ctx.max_value - ctx.min_value) as f64;
+                let rel_val = rel_val.clamp(0.0, 1.0);
+                let rel_val: f64 =
+                    ((rel_val.powi(2) + coef).ln() - coef_ln) / ((rel_val + coef).ln() - coef_ln);
+                let rel_val = rel_val + ctx.palette_offset.rem_euclid(1.0);
+                grad.at(if rel_val > 1.0 {
+                    rel_val - 1.0
+                } else {
+                    rel_val
+                })
+                .to_rgba8()
+            };
+
+            set_pixel(ctx, data, cur_x, cur_y, color[0], color[1], color[2]);
+        }
+    }
+}
+
+/// Computes the whole view of `ctx` and returns it as an RGBA buffer.
+pub fn render_rgba(ctx: &mut Ctx, grad: &colorgrad::Gradient) -> Vec<u8> {
+    let plot = render_plot(ctx);
+    let mut data = vec![0_u8; 4 * ctx.win_width * ctx.win_height];
+    colorize(ctx, grad, &plot, &mut data);
+
+    data
+}
+
+fn set_pixel(ctx: &Ctx, data: &mut [u8], x: usize, y: usize, r: u8, g: u8, b: u8) {
+    let index = (x + y * ctx.win_width) * 4;
+
+    data[index] = r;
+    data[index + 1] = g;
+    data[index + 2] = b;
+    data[index + 3] = 255;
+}
@@ -0,0 +1,138 @@
+use std::io::Write;
+
+use crate::{Ctx, View};
+
+/// Keyword of the PNG text chunk holding the view parameters.
+pub const VIEW_KEYWORD: &str = "mandelbrot-view";
+
+/// Largest width or height of an exported image. A row of poster tiles this wide takes 64 MiB.
+pub const MAX_EXPORT_SIZE: usize = 1 << 16;
+
+pub fn view_to_text(view: &View) -> String {
+    format!(
+        "center_x={}\ncenter_y={}\nhorizontal_span={}\nmax_iters={}",
+        view.center_x, view.center_y, view.horizontal_span, view.max_iters
+    )
+}
+
+pub fn view_from_text(text: &str) -> Option<View> {
+    let mut center_x = None;
+    let mut center_y = None;
+    let mut horizontal_span = None;
+    let mut max_iters = None;
+
+    for line in text.lines() {
+        let (key, value) = line.split_once('=')?;
+        let value = value.trim();
+
+        match key.trim() {
+            "center_x" => center_x = value.parse().ok(),
+            "center_y" => center_y = value.parse().ok(),
+            "horizontal_span" => horizontal_span = value.parse().ok(),
+            "max_iters" => max_iters = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(View {
+        center_x: center_x?,
+        center_y: center_y?,
+        horizontal_span: horizontal_span?,
+        max_iters: max_iters?,
+    })
+}
+
+/// Creates a PNG encoder for an RGBA image of the size of `ctx`, with the view parameters
+/// embedded as a text chunk.
+pub fn png_encoder<W: std::io::Write>(
+    ctx: &Ctx,
+    w: W,
+) -> Result<png::Encoder<'static, W>, png::EncodingError> {
+    let mut encoder = png::Encoder::new(w, ctx.win_width as u32, ctx.win_height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.add_text_chunk("Software".to_string(), "mandelbrot-web".to_string())?;
+    encoder.add_text_chunk(VIEW_KEYWORD.to_string(), view_to_text(&ctx.view()))?;
+
+    Ok(encoder)
+}
+
+/// Encodes the RGBA buffer `data` of the size of `ctx` as PNG.
+pub fn encode_png(ctx: &Ctx, data: &[u8]) -> Result<Vec<u8>, png::EncodingError> {
+    let mut buf = Vec::new();
+
+    {
+        let mut writer = png_encoder(ctx, &mut buf)?.write_header()?;
+        writer.write_image_data(data)?;
+    }
+
+    Ok(buf)
+}
+
+/// Reads the view parameters embedded by [`encode_png`].
+pub fn decode_view(png: &[u8]) -> Option<View> {
+    let reader = png::Decoder::new(png).read_info().ok()?;
+
+    reader
+        .info()
+        .uncompressed_latin1_text
+        .iter()
+        .find(|chunk| chunk.keyword == VIEW_KEYWORD)
+        .and_then(|chunk| view_from_text(&chunk.text))
+}
+
+/// Whether `file_name` names a PNG image, which [`import_png`] may read a view from.
+pub fn is_png(file_name: &str) -> bool {
+    file_name.to_ascii_lowercase().ends_with(".png")
+}
+
+/// Shows the view embedded in an image saved by [`encode_png`] in `ctx`, if it passes
+/// [`Ctx::validate`].
+pub fn import_png(png: &[u8], ctx: &mut Ctx) -> Result<(), String> {
+    let view = decode_view(png).ok_or("The image holds no view parameters.")?;
+
+    let mut imported = *ctx;
+    imported.set_view(&view);
+    imported.validate()?;
+
+    *ctx = imported;
+
+    Ok(())
+}
+
+/// Size of the blocks of a tar archive.
+const TAR_BLOCK: usize = 512;
+
+/// Appends the file `name` with the contents `data` to an uncompressed ustar archive.
+///
+/// Frames of an animation are bundled this way, so that they are saved as one download.
+pub fn write_tar_entry<W: Write>(w: &mut W, name: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut header = [0_u8; TAR_BLOCK];
+    let mut field = |offset: usize, value: &[u8]| {
+        header[offset..offset + value.len()].copy_from_slice(value);
+    };
+
+    field(0, &name.as_bytes()[..name.len().min(100)]);
+    field(100, b"0000644\0");
+    field(108, b"0000000\0");
+    field(116, b"0000000\0");
+    field(124, format!("{:011o}\0", data.len()).as_bytes());
+    field(136, b"00000000000\0");
+    field(156, b"0");
+    field(257, b"ustar\0");
+    field(263, b"00");
+
+    // The checksum is computed with its own field filled with spaces.
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    w.write_all(&header)?;
+    w.write_all(data)?;
+    w.write_all(&[0; TAR_BLOCK][..data.len().next_multiple_of(TAR_BLOCK) - data.len()])
+}
+
+/// Ends a tar archive written with [`write_tar_entry`].
+pub fn finish_tar<W: Write>(w: &mut W) -> std::io::Result<()> {
+    w.write_all(&[0; 2 * TAR_BLOCK])
+}
@@ -0,0 +1,66 @@
+/// Number of fractional bits of the Q4.60 fixed point numbers used for coordinates.
+pub const FRACTION_BITS: u32 = 60;
+
+const FRACTION_MASK: u64 = (1 << FRACTION_BITS) - 1;
+
+/// Extra bits kept while parsing fractions, so that the digits add up without rounding errors.
+const GUARD_BITS: u32 = 40;
+
+/// Formats a Q4.60 number as a decimal. Every such number has a finite decimal expansion of at
+/// most 60 digits, so the result is exact.
+pub fn to_decimal(v: i64) -> String {
+    let abs = v.unsigned_abs();
+    let mut frac = abs & FRACTION_MASK;
+
+    let mut s = String::new();
+    if v < 0 {
+        s.push('-');
+    }
+    s.push_str(&(abs >> FRACTION_BITS).to_string());
+
+    if frac != 0 {
+        s.push('.');
+    }
+    while frac != 0 {
+        let scaled = frac as u128 * 10;
+        s.push(char::from(b'0' + (scaled >> FRACTION_BITS) as u8));
+        frac = (scaled & FRACTION_MASK as u128) as u64;
+    }
+
+    s
+}
+
+/// Parses a decimal into the nearest Q4.60 number. Returns `None` for malformed input and for
+/// values outside of the range, from -8 up to 8.
+pub fn from_decimal(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let int: u128 = if int.is_empty() { 0 } else { int.parse().ok()? };
+    if int > 8 {
+        return None;
+    }
+
+    // The digits are added from the last one, dividing by ten after each.
+    let one = 1_u128 << (FRACTION_BITS + GUARD_BITS);
+    let frac = frac
+        .bytes()
+        .rev()
+        .fold(0_u128, |acc, d| (acc + (d - b'0') as u128 * one) / 10);
+    let frac = (frac + (1 << (GUARD_BITS - 1))) >> GUARD_BITS;
+
+    let abs = ((int << FRACTION_BITS) + frac) as i128;
+
+    i64::try_from(if negative { -abs } else { abs }).ok()
+}
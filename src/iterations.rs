@@ -0,0 +1,101 @@
+use crate::{process_plot_symmetric, render_plot, Ctx, PlotPoint, View};
+
+/// Highest limit the automatic mode raises the iterations to.
+pub const MAX_AUTO_ITERS: usize = 1 << 20;
+
+/// Share of the points at the edge of the set that may be undecided before the automatic mode
+/// raises the limit.
+pub const UNDECIDED_SHARE: f64 = 0.5;
+
+/// Estimates the limit needed at the depth of `view`: the default view's, plus an eighth of it
+/// for every halving of the span.
+pub fn estimate_iters(view: &View) -> usize {
+    let depth = view.magnification().log2().max(0.0);
+
+    (View::default().max_iters as f64 * (1.0 + depth / 8.0)).round() as usize
+}
+
+/// Counts the points at the edge of the set, those that hit the limit next to escaped ones,
+/// and the undecided ones among them, where a neighbour only escaped in the second half of the
+/// limit. Escape times grow towards the boundary, so when the limit is high enough, the
+/// neighbours of the edge escape long before it.
+pub fn edge_points(ctx: &Ctx, plot: &[Vec<PlotPoint>]) -> (usize, usize) {
+    let escaped_at = |x: usize, y: usize| {
+        plot.get(y)
+            .and_then(|row| row.get(x))
+            .filter(|p| p.processed() && !p.stable())
+            .map(|p| p.calculated_value())
+    };
+
+    let (mut edge, mut undecided) = (0, 0);
+
+    for (y, row) in plot.iter().enumerate() {
+        for (x, point) in row.iter().enumerate() {
+            if !point.stable() {
+                continue;
+            }
+
+            let neighbours = [
+                x.checked_sub(1).and_then(|x| escaped_at(x, y)),
+                escaped_at(x + 1, y),
+                y.checked_sub(1).and_then(|y| escaped_at(x, y)),
+                escaped_at(x, y + 1),
+            ];
+            let latest = neighbours.iter().flatten().max();
+
+            if let Some(&latest) = latest {
+                edge += 1;
+                if latest > ctx.max_iters as i128 / 2 {
+                    undecided += 1;
+                }
+            }
+        }
+    }
+
+    (edge, undecided)
+}
+
+/// Whether too many points of `plot` hit the limit of `ctx` near the boundary of the set.
+pub fn needs_more_iters(ctx: &Ctx, plot: &[Vec<PlotPoint>]) -> bool {
+    let (edge, undecided) = edge_points(ctx, plot);
+
+    ctx.max_iters < MAX_AUTO_ITERS && undecided as f64 > edge as f64 * UNDECIDED_SHARE
+}
+
+/// Doubles the limit of `ctx` and reopens the points that hit the old one, see
+/// [`change_iters`].
+pub fn raise_iters(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) {
+    ctx.max_iters = (ctx.max_iters * 2).min(MAX_AUTO_ITERS);
+    change_iters(ctx, plot);
+}
+
+/// Adapts `plot` to the limit of `ctx` and prepares a render that only continues iterating
+/// the points that hit the old, lower limit. Lowering the limit needs no computation at all.
+pub fn change_iters(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) {
+    ctx.reset_min_max();
+
+    for point in plot.iter_mut().flatten() {
+        point.reclassify(ctx.max_iters);
+
+        if point.processed() {
+            ctx.include_value(point.calculated_value());
+        }
+    }
+
+    ctx.rows_processed = 0;
+    ctx.total_rows_processed = 0;
+    ctx.step = 1;
+}
+
+/// Computes every point of the view described by `ctx`, choosing the limit automatically.
+pub fn render_plot_auto(ctx: &mut Ctx) -> Vec<Vec<PlotPoint>> {
+    ctx.max_iters = estimate_iters(&ctx.view());
+    let mut plot = render_plot(ctx);
+
+    while needs_more_iters(ctx, &plot) {
+        raise_iters(ctx, &mut plot);
+        process_plot_symmetric(ctx, &mut plot);
+    }
+
+    plot
+}
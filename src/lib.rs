@@ -1,21 +1,80 @@
 pub use plot_point_mod::PlotPoint;
 
+pub mod animation;
+pub mod bookmarks;
+pub mod cache;
+pub mod color;
+pub mod export;
+pub mod fixed;
+pub mod iterations;
+pub mod navigation;
+pub mod orbit;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod params;
+pub mod poster;
+pub mod precision;
+pub mod settings;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod video;
+
+use std::ops::Range;
+
+use precision::{Arbitrary, Fixed, Float};
+
 use serde::Deserialize;
 use serde::Serialize;
-use web_sys::js_sys::Math;
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Complex {
     pub re: i64,
     pub im: i64,
 }
 
 impl Complex {
-    fn new(re: i64, im: i64) -> Self {
+    pub fn new(re: i64, im: i64) -> Self {
         Self { re, im }
     }
 }
 
+/// The part of [`Ctx`] that describes which region of the plane is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct View {
+    pub center_x: i64,
+    pub center_y: i64,
+    pub horizontal_span: i64,
+    pub max_iters: usize,
+}
+
+impl View {
+    /// How many times smaller the span is than that of the default view.
+    pub fn magnification(&self) -> f64 {
+        View::default().horizontal_span as f64 / self.horizontal_span as f64
+    }
+
+    /// The view that shows a whole Julia set.
+    pub fn julia() -> Self {
+        Self {
+            center_x: 0,
+            center_y: 0,
+            horizontal_span: 4 << 60,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            center_x: -922337203685477580,
+            center_y: 0,
+            horizontal_span: 4035225266123964416,
+            max_iters: 500,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Ctx {
     pub win_height: usize,
@@ -31,11 +90,16 @@ pub struct Ctx {
     pub y_min: i128,
     pub y_max: i128,
 
+    /// Distance between the centers of two neighbouring pixels, the same on both axes.
+    pub pixel_size: i128,
+
     pub max_iters: usize,
 
     pub min_value: i128,
     pub max_value: i128,
 
+    pub strategy: RenderStrategy,
+    /// Number of rows computed between two updates of the canvas.
     pub chunk_size: usize,
     pub rows_processed: usize,
     pub total_rows_processed: usize,
@@ -43,6 +107,29 @@ pub struct Ctx {
     pub offset: usize,
 
     pub brightness: f64,
+    /// Shift of the escaped colors along the gradient, as a fraction of its length.
+    pub palette_offset: f64,
+
+    /// The `c` of the Julia set to plot, or `None` to plot the Mandelbrot set.
+    pub julia: Option<Complex>,
+
+    /// Whether rows mirroring others across the real axis are copied rather than computed.
+    pub symmetry: bool,
+
+    /// Radius beyond which a point counts as escaped, between [`MIN_BAILOUT`] and
+    /// [`MAX_BAILOUT`].
+    pub bailout: f64,
+
+    /// Number representation the points are computed with.
+    pub precision: Precision,
+
+    /// Whether the worker picks `max_iters` itself, see [`iterations`].
+    pub auto_iters: bool,
+
+    /// Whether a render starts with coarse passes, see [`process_plot_progressive`].
+    pub progressive: bool,
+    /// Distance between the points computed by the current pass, 1 for the full resolution.
+    pub step: usize,
 
     pub needs_recalc: bool,
 }
@@ -60,14 +147,24 @@ impl Default for Ctx {
             x_max: Default::default(),
             y_min: Default::default(),
             y_max: Default::default(),
+            pixel_size: Default::default(),
             max_iters: 500,
-            min_value: i128::max_value(),
+            min_value: i128::MAX,
             max_value: Default::default(),
+            strategy: Default::default(),
             chunk_size: 128,
             rows_processed: Default::default(),
             total_rows_processed: Default::default(),
             offset: Default::default(),
             brightness: 0.6,
+            palette_offset: Default::default(),
+            julia: Default::default(),
+            symmetry: true,
+            bailout: MIN_BAILOUT,
+            precision: Default::default(),
+            auto_iters: false,
+            progressive: true,
+            step: 1,
             needs_recalc: true,
         }
     }
@@ -77,7 +174,7 @@ impl Ctx {
     pub fn define_bounds_from(&mut self, x: usize, y: usize, w: usize, h: usize) {
         let cx = self.get_x(x + w / 2);
         let cy = self.get_y(y + h / 2);
-        let hs = self.horizontal_span * w as i128 / self.win_width as i128;
+        let hs = self.pixel_size * w as i128;
 
         self.define_bounds(cx, cy, hs as i64);
     }
@@ -86,54 +183,268 @@ impl Ctx {
         self.center_x = center_x as i128;
         self.center_y = center_y as i128;
         self.horizontal_span = horizontal_span as i128;
-        self.vertical_span =
-            (self.horizontal_span * self.win_height as i128) / self.win_width as i128;
+        self.pixel_size = (self.horizontal_span / self.win_width as i128).max(1);
+        self.bounds_from_pixel_size();
+    }
+
+    /// Places the pixel grid of `pixel_size` so that the center falls on pixel
+    /// (`win_width / 2`, `win_height / 2`).
+    fn bounds_from_pixel_size(&mut self) {
+        self.vertical_span = self.pixel_size * self.win_height as i128;
+        self.x_min = self.center_x - self.pixel_size * (self.win_width / 2) as i128;
+        self.x_max = self.x_min + self.pixel_size * self.win_width as i128;
+        self.y_max = self.center_y + self.pixel_size * (self.win_height / 2) as i128;
+        self.y_min = self.y_max - self.vertical_span;
+    }
 
-        self.x_min = self.center_x - self.horizontal_span / 2;
-        self.x_max = self.x_min + self.horizontal_span;
-        self.y_min = self.center_y - self.vertical_span / 2;
-        self.y_max = self.y_min + self.vertical_span;
+    pub fn view(&self) -> View {
+        View {
+            center_x: self.center_x as i64,
+            center_y: self.center_y as i64,
+            horizontal_span: self.horizontal_span as i64,
+            max_iters: self.max_iters,
+        }
+    }
+
+    pub fn set_view(&mut self, view: &View) {
+        self.max_iters = view.max_iters;
+        self.define_bounds(view.center_x, view.center_y, view.horizontal_span);
+    }
+
+    /// Returns a copy of the context showing the same view at a different resolution, which
+    /// must be at least one pixel in each direction.
+    pub fn resized(&self, win_width: usize, win_height: usize) -> Result<Ctx, String> {
+        if win_width == 0 || win_height == 0 {
+            return Err("The width and height must be positive.".to_string());
+        }
+
+        let mut ctx = Ctx {
+            win_width,
+            win_height,
+            ..*self
+        };
+        ctx.set_view(&self.view());
+
+        Ok(ctx)
+    }
+
+    /// Changes the resolution, keeping the center and the size of a pixel.
+    pub fn resize(&mut self, win_width: usize, win_height: usize) {
+        self.win_width = win_width;
+        self.win_height = win_height;
+        self.horizontal_span = self.pixel_size * win_width as i128;
+        self.bounds_from_pixel_size();
+    }
+
+    /// Returns a context covering the `w` x `h` pixels at `x`, `y` of this one. Its pixels
+    /// are exactly the pixels of this context.
+    pub fn sub_ctx(&self, x: usize, y: usize, w: usize, h: usize) -> Ctx {
+        let x_min = self.get_x(x) as i128;
+        let y_max = self.get_y(y) as i128;
+
+        let mut ctx = Ctx {
+            win_width: w,
+            win_height: h,
+            horizontal_span: self.pixel_size * w as i128,
+            ..*self
+        };
+        ctx.center_x = x_min + self.pixel_size * (w / 2) as i128;
+        ctx.center_y = y_max - self.pixel_size * (h / 2) as i128;
+        ctx.bounds_from_pixel_size();
+
+        ctx
     }
 
     pub fn apply_changes(&mut self, other: &Ctx) {
         self.min_value = other.min_value;
         self.max_value = other.max_value;
         self.brightness = other.brightness;
+        self.palette_offset = other.palette_offset;
+
+        if other.auto_iters {
+            self.max_iters = other.max_iters;
+        }
+    }
+
+    /// Prepares the render state for a new render of the whole view.
+    pub fn start_render(&mut self) {
+        self.rows_processed = 0;
+        self.total_rows_processed = 0;
+        self.step = if self.progressive { PREVIEW_STEP } else { 1 };
+    }
+
+    /// Rows the current chunk spans. A coarse pass computes one point in `step * step`, so its
+    /// chunks span that many times more rows for about the same work, and each coarse pass
+    /// takes about as long as a single chunk of the final one.
+    pub fn chunk_rows(&self) -> usize {
+        self.chunk_size * self.step * self.step
+    }
+
+    /// Moves on to the next chunk of rows, or to the start of the next pass once a coarse one
+    /// is complete.
+    pub fn next_chunk(&mut self) {
+        self.rows_processed += self.chunk_rows();
+
+        if self.step > 1 && self.rows_processed >= self.win_height {
+            self.step /= 2;
+            self.rows_processed = 0;
+        }
+    }
+
+    /// Whether both contexts map every pixel to the same point, so that their plots are
+    /// interchangeable apart from the iteration limit.
+    pub fn same_points(&self, other: &Ctx) -> bool {
+        self.win_width == other.win_width
+            && self.win_height == other.win_height
+            && self.x_min == other.x_min
+            && self.y_max == other.y_max
+            && self.pixel_size == other.pixel_size
+            && self.julia == other.julia
+            && self.bailout == other.bailout
+            && self.backend() == other.backend()
+    }
+
+    /// The representation the points are computed with, with [`Precision::Auto`] resolved.
+    pub fn backend(&self) -> Precision {
+        match self.precision {
+            Precision::Auto => Precision::for_pixel_size(self.pixel_size),
+            precision => precision,
+        }
+    }
+
+    /// Squared bailout radius in Q4.60, which `z` is compared against.
+    pub fn threshold(&self) -> i128 {
+        (self.bailout * self.bailout * (1_u64 << 60) as f64) as i128
+    }
+
+    /// Checks the settings that users can change, so that invalid ones never reach the worker.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_CHUNK_SIZE).contains(&self.chunk_size) {
+            return Err(format!(
+                "The chunk size must be between 1 and {MAX_CHUNK_SIZE}."
+            ));
+        }
+        if !(MIN_BAILOUT..=MAX_BAILOUT).contains(&self.bailout) {
+            return Err(format!(
+                "The bailout radius must be between {MIN_BAILOUT} and {MAX_BAILOUT}."
+            ));
+        }
+        if self.max_iters == 0 {
+            return Err("The iteration limit must be positive.".to_string());
+        }
+        if self.horizontal_span <= 0 {
+            return Err("The span must be positive.".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Widens the value range to include `value`.
+    pub fn include_value(&mut self, value: i128) {
+        self.min_value = self.min_value.min(value);
+        self.max_value = self.max_value.max(value);
     }
 
     pub fn reset_min_max(&mut self) {
         self.needs_recalc = true;
-        self.min_value = i128::max_value();
+        self.min_value = i128::MAX;
         self.max_value = Default::default();
     }
 
     pub fn get_x(&self, x: usize) -> i64 {
-        let x_percent = (x as f64 * 10000.0 / self.win_width as f64) as i128;
-        (self.x_min + (self.x_max - self.x_min) * x_percent / 10000) as i64
+        self.get_x_sub((x as i128) << SUBPIXEL_BITS)
     }
 
     pub fn get_y(&self, y: usize) -> i64 {
-        let y_percent = (y as f64 * 10000.0 / self.win_height as f64) as i128;
-        (self.y_max - (self.y_max - self.y_min) * y_percent / 10000) as i64
-    }
-
-    pub fn revert_y(&self, y: i64) -> (usize, Symmetry) {
-        let p =
-            (self.y_max - y as i128) * (self.win_height as i128 - 1) / (self.y_max - self.y_min);
-        let rem = p % 1;
-        let p1 = p - rem;
-        let third = 1 / 3;
-
-        (
-            p1 as usize,
-            if p < p1 + third {
-                Symmetry::OverTwo
-            } else if p > p1 + third && p < p1 + third * 2 {
-                Symmetry::OverOne
-            } else {
-                Symmetry::Exact
-            },
-        )
+        self.get_y_sub((y as i128) << SUBPIXEL_BITS)
+    }
+
+    /// Maps the horizontal position `x`, in units of 1/2^[`SUBPIXEL_BITS`] pixel, to the plane.
+    pub fn get_x_sub(&self, x: i128) -> i64 {
+        (self.x_min + ((self.pixel_size * x) >> SUBPIXEL_BITS)) as i64
+    }
+
+    /// Maps the vertical position `y`, in units of 1/2^[`SUBPIXEL_BITS`] pixel, to the plane.
+    pub fn get_y_sub(&self, y: i128) -> i64 {
+        (self.y_max - ((self.pixel_size * y) >> SUBPIXEL_BITS)) as i64
+    }
+
+    /// Inverse of [`Ctx::get_x_sub`]: returns the first sub-pixel position mapped to `x` or
+    /// beyond.
+    pub fn revert_x(&self, x: i64) -> i128 {
+        div_ceil((x as i128 - self.x_min) << SUBPIXEL_BITS, self.pixel_size)
+    }
+
+    /// Inverse of [`Ctx::get_y_sub`]: returns the first sub-pixel position mapped to `y` or
+    /// below.
+    pub fn revert_y(&self, y: i64) -> i128 {
+        div_ceil((self.y_max - y as i128) << SUBPIXEL_BITS, self.pixel_size)
+    }
+
+    /// Whether the plot is symmetric to the real axis, and mirroring is enabled.
+    pub fn real_axis_symmetric(&self) -> bool {
+        self.symmetry && self.julia.is_none_or(|c| c.im == 0)
+    }
+
+    /// Returns where the real axis lies relative to the rows of pixels.
+    pub fn real_axis(&self) -> RealAxis {
+        let twice_axis = 2 * self.y_max;
+
+        if twice_axis.rem_euclid(self.pixel_size) != 0 {
+            return RealAxis::None;
+        }
+
+        let y = self.y_max.div_euclid(self.pixel_size);
+        let on_row = self.y_max.rem_euclid(self.pixel_size) == 0;
+        let last = if on_row { y } else { y + 1 };
+
+        if y < 0 || last >= self.win_height as i128 {
+            RealAxis::None
+        } else if on_row {
+            RealAxis::Row(y as usize)
+        } else {
+            RealAxis::BetweenRows(y as usize)
+        }
+    }
+
+    /// Returns the row whose coordinates are the exact negatives of the coordinates of row `y`,
+    /// if there is one and the plot is symmetric.
+    pub fn mirror_row(&self, y: usize) -> Option<usize> {
+        let twice_axis = 2 * self.y_max;
+
+        if !self.real_axis_symmetric() || twice_axis.rem_euclid(self.pixel_size) != 0 {
+            return None;
+        }
+
+        let mirror = twice_axis / self.pixel_size - y as i128;
+
+        (0..self.win_height as i128)
+            .contains(&mirror)
+            .then_some(mirror as usize)
+    }
+
+    /// Returns the rows which have to be computed. Every row outside of them mirrors one of
+    /// them across the real axis.
+    pub fn computed_rows(&self) -> Range<usize> {
+        let height = self.win_height;
+
+        if !self.real_axis_symmetric() {
+            return 0..height;
+        }
+
+        // `last_upper` is the last row of the upper half, which includes a row on the axis.
+        let (above, last_upper) = match self.real_axis() {
+            RealAxis::Row(y) => (y, y),
+            RealAxis::BetweenRows(y) => (y + 1, y),
+            RealAxis::None => return 0..height,
+        };
+        let below = height - 1 - last_upper;
+
+        if above >= below {
+            0..last_upper + 1
+        } else {
+            above..height
+        }
     }
 
     pub fn get_coords(&self, x: usize, y: usize) -> (i64, i64) {
@@ -141,27 +452,133 @@ impl Ctx {
     }
 }
 
-#[derive(Debug)]
-pub enum Symmetry {
-    Exact,
-    OverOne,
-    OverTwo,
+/// Number of fractional bits of the sub-pixel positions taken by [`Ctx::get_x_sub`] and
+/// [`Ctx::get_y_sub`].
+pub const SUBPIXEL_BITS: u32 = 16;
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    let q = a.div_euclid(b);
+    if a.rem_euclid(b) == 0 {
+        q
+    } else {
+        q + 1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenderStrategy {
+    /// Every point is computed.
+    BruteForce,
+    /// Only the borders between areas of equal value are computed, see [`process_plot_cpu`].
+    #[default]
+    Tracing,
+    /// Rectangles with a uniform border are filled, others are split in two, see
+    /// [`process_plot_cpu`]. Faster than tracing, but may miss details thinner than a pixel
+    /// that cross no border.
+    Subdivision,
+}
+
+impl RenderStrategy {
+    pub const ALL: [RenderStrategy; 3] = [
+        RenderStrategy::BruteForce,
+        RenderStrategy::Tracing,
+        RenderStrategy::Subdivision,
+    ];
+
+    /// Name used in the controls and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderStrategy::BruteForce => "brute-force",
+            RenderStrategy::Tracing => "tracing",
+            RenderStrategy::Subdivision => "subdivision",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Precision {
+    /// The cheapest representation that is accurate at the current depth, see
+    /// [`Precision::for_pixel_size`].
+    #[default]
+    Auto,
+    /// Native `f64`, the fastest, accurate down to pixel spacings of about 2^-42.
+    Float,
+    /// Q4.60 fixed point, accurate down to pixel spacings of about 2^-50.
+    Fixed,
+    /// Big integers with [`precision::ARBITRARY_FRACTION_BITS`] fractional bits, and by far
+    /// the slowest. Accurate down to the smallest pixel spacing the Q4.60 coordinates allow,
+    /// 2^-60, which bounds the depth of every backend.
+    Arbitrary,
+}
+
+impl Precision {
+    pub const ALL: [Precision; 4] = [
+        Precision::Auto,
+        Precision::Float,
+        Precision::Fixed,
+        Precision::Arbitrary,
+    ];
+
+    /// Name used in the controls and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Precision::Auto => "auto",
+            Precision::Float => "float",
+            Precision::Fixed => "fixed",
+            Precision::Arbitrary => "arbitrary",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// Returns the cheapest representation that is accurate for points `pixel_size` apart.
+    pub fn for_pixel_size(pixel_size: i128) -> Precision {
+        if pixel_size >= precision::MIN_FLOAT_PIXEL_SIZE {
+            Precision::Float
+        } else if pixel_size >= precision::MIN_FIXED_PIXEL_SIZE {
+            Precision::Fixed
+        } else {
+            Precision::Arbitrary
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RealAxis {
+    /// The axis passes through the centers of the pixels of the row.
+    Row(usize),
+    /// The axis lies exactly halfway between the row and the next one.
+    BetweenRows(usize),
+    /// The axis is outside of the plot, or the rows are not placed symmetrically to it.
+    None,
 }
 
 mod plot_point_mod {
+    use super::Complex;
+
     #[derive(Default, Clone, Copy)]
     pub struct PlotPoint {
         val: i128,
         i: usize,
+        /// Where the iteration stopped, for points that did not escape.
+        z: Option<Complex>,
 
         calc_val: Option<i128>,
+        /// Whether the point did not escape within the limit it was last classified with.
+        stable: bool,
 
         pub filled: bool,
     }
 
     impl PlotPoint {
         pub fn new_from<T: FnMut(&mut PlotPoint)>(other: &PlotPoint, mut upd: T) -> PlotPoint {
-            let mut new = other.clone();
+            let mut new = *other;
             upd(&mut new);
 
             new
@@ -175,6 +592,53 @@ mod plot_point_mod {
             self.i = i;
             // self.calc_val = Some((i as f64 - val.log10().log2()).log10())
             self.calc_val = Some(i as i128);
+            self.stable = false;
+        }
+
+        /// Records that the point did not escape within `i` iterations, `z` being its next value.
+        pub fn stop(&mut self, z: Complex, i: usize) {
+            self.calculate(0, i);
+            self.z = Some(z);
+            self.stable = true;
+        }
+
+        /// Classifies the point again for the limit `max_iters`, without computing anything.
+        ///
+        /// Points that escaped later than the limit become stable. Points that were stopped
+        /// by a lower limit are left unprocessed, ready to be iterated further, see
+        /// [`PlotPoint::resume_state`]; filled ones have no state of their own and are reset.
+        pub fn reclassify(&mut self, max_iters: usize) {
+            if self.calc_val.is_none() {
+                return;
+            }
+
+            if self.val != 0 {
+                self.calc_val = Some(self.i.min(max_iters) as i128);
+                self.stable = self.i > max_iters;
+            } else if self.i >= max_iters {
+                self.calc_val = Some(max_iters as i128);
+                self.stable = true;
+            } else if self.filled {
+                self.reset();
+            } else {
+                self.calc_val = None;
+            }
+        }
+
+        /// The point at the complex conjugate of this one.
+        pub fn mirrored(&self) -> PlotPoint {
+            PlotPoint::new_from(self, |p| {
+                p.z = p.z.map(|z| Complex::new(z.re, -z.im));
+            })
+        }
+
+        /// The value to continue iterating from and the number of iterations done, if the point
+        /// was reopened.
+        pub fn resume_state(&self) -> Option<(Complex, usize)> {
+            match (self.calc_val, self.z) {
+                (None, Some(z)) => Some((z, self.i)),
+                _ => None,
+            }
         }
 
         pub fn processed(&self) -> bool {
@@ -182,7 +646,14 @@ mod plot_point_mod {
         }
 
         pub fn stable(&self) -> bool {
-            self.calc_val.is_some() && self.val == 0
+            self.calc_val.is_some() && self.stable
+        }
+
+        /// Whether both points are processed and have the same color.
+        pub fn same_as(&self, other: &PlotPoint) -> bool {
+            self.calc_val.is_some()
+                && self.calc_val == other.calc_val
+                && self.stable() == other.stable()
         }
 
         pub fn calculated_value(&self) -> i128 {
@@ -192,215 +663,400 @@ mod plot_point_mod {
 
         pub fn reset(&mut self) {
             self.calc_val = None;
+            self.z = None;
             self.filled = false;
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum TraceDirection {
-    Right,
-    RightDown,
-    Down,
-    LeftDown,
-    Left,
-    LeftUp,
-    Up,
-    RightUp,
+/// Computes every point of the view described by `ctx` in one go.
+pub fn render_plot(ctx: &mut Ctx) -> Vec<Vec<PlotPoint>> {
+    let mut plot = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+
+    ctx.reset_min_max();
+    ctx.offset = 0;
+    ctx.rows_processed = 0;
+    ctx.step = 1;
+    ctx.chunk_size = ctx.win_height;
+    process_plot_symmetric(ctx, &mut plot);
+
+    plot
 }
 
-impl TraceDirection {
-    pub fn get_index(&self) -> usize {
-        match self {
-            TraceDirection::Right => 0,
-            TraceDirection::RightDown => 1,
-            TraceDirection::Down => 2,
-            TraceDirection::LeftDown => 3,
-            TraceDirection::Left => 4,
-            TraceDirection::LeftUp => 5,
-            TraceDirection::Up => 6,
-            TraceDirection::RightUp => 7,
+/// Distance between the points computed by the first, coarsest pass of a progressive render.
+pub const PREVIEW_STEP: usize = 8;
+
+/// Processes the next chunk of rows of the current pass.
+///
+/// Coarse passes compute every `ctx.step`-th point of every `ctx.step`-th row, skipping those
+/// known from the previous pass, so each one doubles the resolution. The final pass fills in
+/// the rest with [`process_plot_symmetric`]. Returns the number of rows completed by it.
+pub fn process_plot_progressive(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) -> usize {
+    if ctx.step <= 1 {
+        return process_plot_symmetric(ctx, plot);
+    }
+
+    let step = ctx.step;
+    let end = (ctx.rows_processed + ctx.chunk_rows()).min(plot.len());
+
+    for y in (ctx.rows_processed..end).filter(|y| y % step == 0) {
+        for x in (0..ctx.win_width).step_by(step) {
+            let (cx, cy) = ctx.get_coords(x, y);
+            calculate_point(ctx, &mut plot[y][x], cx, cy);
         }
     }
+
+    0
 }
 
-pub fn near_border(plot: &[Vec<PlotPoint>], x: i16, y: usize) -> bool {
-    if x < 0 {
-        return false;
-    }
+/// Processes the next chunk of rows like [`process_plot_cpu`], copying the rows that mirror
+/// processed ones across the real axis instead of computing them. `plot` covers the whole
+/// view of `ctx`.
+pub fn process_plot_symmetric(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) -> usize {
+    let rows = ctx.computed_rows();
+    let chunk = ctx.rows_processed..(ctx.rows_processed + ctx.chunk_size).min(rows.len());
 
-    let point = plot[y][x as usize];
+    ctx.offset = rows.start;
+    let rows_processed = process_plot_cpu(ctx, &mut plot[rows.clone()]);
 
-    point.stable()
+    rows_processed + mirror_rows(ctx, plot, &rows, &chunk)
 }
 
-pub fn process_plot_cpu(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) -> usize {
+/// Copies the rows outside of `rows` whose mirror is one of the computed rows in `chunk`,
+/// counted from `rows.start`. Returns the number of rows copied.
+pub(crate) fn mirror_rows(
+    ctx: &Ctx,
+    plot: &mut [Vec<PlotPoint>],
+    rows: &Range<usize>,
+    chunk: &Range<usize>,
+) -> usize {
     let mut rows_processed = 0;
-    let mut cur_y = ctx.rows_processed;
-    while cur_y < (ctx.rows_processed + ctx.chunk_size).min(plot.len()) {
-        let mut cur_x = 0;
-        while cur_x < ctx.win_width {
-            let (cx, cy) = ctx.get_coords(cur_x, cur_y + ctx.offset);
 
-            let plot_point = &mut plot[cur_y][cur_x];
+    for y in (0..rows.start).chain(rows.end..plot.len()) {
+        let src = ctx
+            .mirror_row(y)
+            .expect("Rows outside of the computed ones should have a mirror.");
 
-            if calculate_point(ctx, plot_point, cx, cy) {
-                cur_x += 1;
-                continue;
+        if chunk.contains(&(src - rows.start)) {
+            let (src_row, dst_row) = if src < y {
+                let (src_rows, dst_rows) = plot.split_at_mut(y);
+                (&src_rows[src], &mut dst_rows[0])
+            } else {
+                let (dst_rows, src_rows) = plot.split_at_mut(src);
+                (&src_rows[0], &mut dst_rows[y])
+            };
+            for (dst, src) in dst_row.iter_mut().zip(src_row) {
+                *dst = src.mirrored();
             }
+            rows_processed += 1;
+        }
+    }
 
-            if plot_point.stable() {
-                if near_border(plot, cur_x as i16 - 1, cur_y) {
-                    cur_x = process_fast(ctx, plot, cur_x + 1, cur_y);
-                } else {
-                    trace_edge(ctx, plot, cur_x, cur_y);
-                }
-            }
+    rows_processed
+}
 
-            cur_x += 1;
+/// Processes the next chunk of rows of `plot`, which starts `ctx.offset` rows into the view.
+pub fn process_plot_cpu(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) -> usize {
+    let start = ctx.rows_processed.min(plot.len());
+    let end = (ctx.rows_processed + ctx.chunk_size).min(plot.len());
+    let chunk = &mut plot[start..end];
+    let first_row = ctx.offset + start;
+
+    match ctx.strategy {
+        RenderStrategy::BruteForce => process_brute_force(ctx, chunk, first_row),
+        RenderStrategy::Tracing => process_tracing(ctx, chunk, first_row),
+        RenderStrategy::Subdivision => {
+            if !chunk.is_empty() && ctx.win_width > 0 {
+                let (right, bottom) = (ctx.win_width - 1, chunk.len() - 1);
+                process_subdivision(ctx, chunk, first_row, (0, 0), (right, bottom));
+            }
         }
-
-        cur_y += 1;
-        rows_processed += 1;
     }
 
-    rows_processed
+    end - start
 }
 
-fn process_fast(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>], x: usize, y: usize) -> usize {
-    let mut cur_x = x;
+fn process_brute_force(ctx: &mut Ctx, chunk: &mut [Vec<PlotPoint>], first_row: usize) {
+    #[cfg(feature = "simd")]
+    if ctx.backend() == Precision::Float {
+        return simd::process_brute_force(ctx, chunk, first_row);
+    }
 
-    let max = Math::min(
-        (ctx.win_width - ((ctx.win_width - 1) % 1) - 1) as f64,
-        (ctx.win_width - 1) as f64,
-    ) as usize;
+    for (y, row) in chunk.iter_mut().enumerate() {
+        for (x, plot_point) in row.iter_mut().enumerate() {
+            let (cx, cy) = ctx.get_coords(x, first_row + y);
+            calculate_point(ctx, plot_point, cx, cy);
+        }
+    }
+}
+
+/// Computes the borders between areas of equal value and fills the areas in between.
+///
+/// Starting from the edges of the chunk, the neighbours of every computed point are computed
+/// as well, and wherever two of them differ, the search continues from both. Points that are
+/// never reached this way are enclosed by points of a single value, and get that value. Every
+/// point is computed at most once, and points processed beforehand are taken as they are.
+fn process_tracing(ctx: &mut Ctx, chunk: &mut [Vec<PlotPoint>], first_row: usize) {
+    let height = chunk.len();
+    let width = ctx.win_width;
+
+    if height == 0 || width == 0 {
+        return;
+    }
 
-    loop {
-        let plot_point = &mut plot[y][cur_x];
+    let mut queued = vec![false; width * height];
+    let mut queue = Vec::new();
 
-        if plot_point.processed() && (!plot_point.stable() || (plot_point.stable() && cur_x == max))
-        {
-            return cur_x;
-        } else if !plot_point.stable() {
-            plot_point.calculate(0, ctx.max_iters);
-            plot_point.filled = true;
+    let mut enqueue = |queue: &mut Vec<(usize, usize)>, x: usize, y: usize| {
+        if !queued[y * width + x] {
+            queued[y * width + x] = true;
+            queue.push((x, y));
         }
+    };
 
-        cur_x += 1;
+    for x in 0..width {
+        enqueue(&mut queue, x, 0);
+        enqueue(&mut queue, x, height - 1);
     }
-}
+    for y in 0..height {
+        enqueue(&mut queue, 0, y);
+        enqueue(&mut queue, width - 1, y);
+    }
+    // Points known beforehand, e.g. from a coarse pass, may lie inside an area they differ from.
+    for (y, row) in chunk.iter().enumerate() {
+        for (x, point) in row.iter().enumerate() {
+            if point.processed() {
+                enqueue(&mut queue, x, y);
+            }
+        }
+    }
+
+    while let Some((x, y)) = queue.pop() {
+        let center = *load_point(ctx, chunk, first_row, x, y);
+        let mut differs =
+            |nx: usize, ny: usize| !load_point(ctx, chunk, first_row, nx, ny).same_as(&center);
+
+        let has_left = x > 0;
+        let has_right = x + 1 < width;
+        let has_up = y > 0;
+        let has_down = y + 1 < height;
 
-fn trace_edge(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>], x: usize, y: usize) {
-    let mut cur_x = x;
-    let mut cur_y = y;
-    let mut cur_dir = TraceDirection::Right;
+        let left = has_left && differs(x - 1, y);
+        let right = has_right && differs(x + 1, y);
+        let up = has_up && differs(x, y - 1);
+        let down = has_down && differs(x, y + 1);
 
-    loop {
-        (cur_x, cur_y, cur_dir) = process_neighbours(ctx, plot, cur_x, cur_y, cur_dir);
+        if left {
+            enqueue(&mut queue, x - 1, y);
+        }
+        if right {
+            enqueue(&mut queue, x + 1, y);
+        }
+        if up {
+            enqueue(&mut queue, x, y - 1);
+        }
+        if down {
+            enqueue(&mut queue, x, y + 1);
+        }
+        if has_up && has_left && (left || up) {
+            enqueue(&mut queue, x - 1, y - 1);
+        }
+        if has_up && has_right && (right || up) {
+            enqueue(&mut queue, x + 1, y - 1);
+        }
+        if has_down && has_left && (left || down) {
+            enqueue(&mut queue, x - 1, y + 1);
+        }
+        if has_down && has_right && (right || down) {
+            enqueue(&mut queue, x + 1, y + 1);
+        }
+    }
 
-        if cur_x == x && cur_y == y {
-            return;
+    // The first column is part of the edge, so every unprocessed point has a processed one
+    // to its left.
+    for row in chunk.iter_mut() {
+        for x in 1..width {
+            if !row[x].processed() {
+                row[x] = PlotPoint::new_from(&row[x - 1], |p| p.filled = true);
+            }
         }
     }
 }
 
-fn process_neighbours(
+/// Below this many pixels on a side, a rectangle is computed rather than split further.
+const SUBDIVISION_MIN_SIZE: usize = 6;
+
+/// Processes the rectangle between the corners `top_left` and `bottom_right`, inclusive.
+///
+/// This is the Mariani–Silver algorithm: when the whole border of the rectangle has the same
+/// value, so does the inside, otherwise it is split in two along the longer side. The halves
+/// share the middle line, so no border is computed twice.
+fn process_subdivision(
     ctx: &mut Ctx,
-    plot: &mut [Vec<PlotPoint>],
-    x: usize,
-    y: usize,
-    direction: TraceDirection,
-) -> (usize, usize, TraceDirection) {
-    static NEIGHBOURS: [(i16, i16, TraceDirection); 8] = [
-        (1, 0, TraceDirection::Right),
-        (1, 1, TraceDirection::RightDown),
-        (0, 1, TraceDirection::Down),
-        (-1, 1, TraceDirection::LeftDown),
-        (-1, 0, TraceDirection::Left),
-        (-1, -1, TraceDirection::LeftUp),
-        (0, -1, TraceDirection::Up),
-        (1, -1, TraceDirection::RightUp),
-    ];
+    chunk: &mut [Vec<PlotPoint>],
+    first_row: usize,
+    top_left: (usize, usize),
+    bottom_right: (usize, usize),
+) {
+    let (left, top) = top_left;
+    let (right, bottom) = bottom_right;
+    let (width, height) = (right - left + 1, bottom - top + 1);
+
+    if width < SUBDIVISION_MIN_SIZE || height < SUBDIVISION_MIN_SIZE {
+        for y in top..=bottom {
+            for x in left..=right {
+                load_point(ctx, chunk, first_row, x, y);
+            }
+        }
+        return;
+    }
+
+    let first = *load_point(ctx, chunk, first_row, left, top);
+    let mut uniform = true;
+
+    for x in left..=right {
+        uniform &= load_point(ctx, chunk, first_row, x, top).same_as(&first);
+        uniform &= load_point(ctx, chunk, first_row, x, bottom).same_as(&first);
+    }
+    for y in top..=bottom {
+        uniform &= load_point(ctx, chunk, first_row, left, y).same_as(&first);
+        uniform &= load_point(ctx, chunk, first_row, right, y).same_as(&first);
+    }
 
-    let mut next: (usize, usize, TraceDirection) = (x, y, TraceDirection::Right);
-
-    for n in NEIGHBOURS
-        .iter()
-        .rev()
-        .cycle()
-        .skip(8 - direction.get_index() + 3)
-        .take(8)
-    {
-        let nx = x as i16 + n.0;
-        let ny = y as i16 + n.1;
-
-        if nx >= 0 && nx < ctx.win_width as i16 {
-            if ny >= 0 && ny < plot.len() as i16 {
-                if process_point(ctx, plot, nx as usize, ny as usize) {
-                    // web_sys::console::log_1(&"Yes".into());
-                    next = (nx as usize, ny as usize, n.2)
+    if uniform {
+        for row in &mut chunk[top + 1..bottom] {
+            for point in &mut row[left + 1..right] {
+                if !point.processed() {
+                    *point = PlotPoint::new_from(&first, |p| p.filled = true);
                 }
             }
         }
+    } else if width >= height {
+        let middle = left + width / 2;
+        process_subdivision(ctx, chunk, first_row, (left, top), (middle, bottom));
+        process_subdivision(ctx, chunk, first_row, (middle, top), (right, bottom));
+    } else {
+        let middle = top + height / 2;
+        process_subdivision(ctx, chunk, first_row, (left, top), (right, middle));
+        process_subdivision(ctx, chunk, first_row, (left, middle), (right, bottom));
     }
-
-    next
 }
 
-fn process_point(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>], x: usize, y: usize) -> bool {
-    let plot_point = &mut plot[y][x];
+fn load_point<'a>(
+    ctx: &mut Ctx,
+    chunk: &'a mut [Vec<PlotPoint>],
+    first_row: usize,
+    x: usize,
+    y: usize,
+) -> &'a PlotPoint {
+    let plot_point = &mut chunk[y][x];
 
-    if plot_point.processed() {
-        return plot_point.stable();
+    if !plot_point.processed() {
+        let (cx, cy) = ctx.get_coords(x, first_row + y);
+        calculate_point(ctx, plot_point, cx, cy);
     }
 
-    let (cx, cy) = ctx.get_coords(x, y + ctx.offset);
-    calculate_point(ctx, plot_point, cx, cy);
+    plot_point
+}
 
-    plot_point.stable()
+/// Computes the point at `x`, `y` of the view of `ctx` on its own.
+pub fn compute_point(ctx: &Ctx, x: usize, y: usize) -> PlotPoint {
+    let mut point = PlotPoint::default();
+    calculate_point(&mut ctx.clone(), &mut point, ctx.get_x(x), ctx.get_y(y));
+
+    point
 }
 
-fn calculate_point(ctx: &mut Ctx, plot_point: &mut PlotPoint, cx: i64, cy: i64) -> bool {
+pub(crate) fn calculate_point(ctx: &mut Ctx, plot_point: &mut PlotPoint, cx: i64, cy: i64) -> bool {
     if plot_point.processed() {
         return true;
     }
 
-    mandelbrot_val_at_point(Complex::new(cx, cy), ctx.max_iters, plot_point);
+    let point = Complex::new(cx, cy);
+    let c = ctx.julia.unwrap_or(point);
 
-    let calc_value = plot_point.calculated_value() as i128;
+    let threshold = ctx.threshold();
+    let precision = ctx.backend();
 
-    if calc_value < ctx.min_value {
-        ctx.min_value = calc_value;
+    match plot_point.resume_state() {
+        Some((z, i)) if i < ctx.max_iters => {
+            iterate(precision, z, c, i + 1, ctx.max_iters, threshold, plot_point)
+        }
+        _ => {
+            plot_point.reset();
+            mandelbrot_val_at_point(point, c, ctx.max_iters, threshold, precision, plot_point)
+        }
     }
 
-    if calc_value > ctx.max_value {
-        ctx.max_value = calc_value;
-    }
+    ctx.include_value(plot_point.calculated_value());
 
     false
 }
 
-fn mandelbrot_val_at_point(c: Complex, max_iters: usize, p: &mut PlotPoint) {
-    // 4 * 2 ^ 60
-    static THRESHOLD: i128 = 4 << 60;
+/// Smallest bailout radius; points further than 2 from the origin always escape.
+pub const MIN_BAILOUT: f64 = 2.0;
 
-    let mut z = c.clone();
+/// Largest bailout radius. `z^2 + c` of a point inside a larger radius can overflow Q4.60.
+pub const MAX_BAILOUT: f64 = 2.4;
 
-    for i in 0..=max_iters {
-        let re_sq = (z.re as i128).pow(2) >> 60;
-        let im_sq = (z.im as i128).pow(2) >> 60;
+/// Largest number of rows computed between two updates of the canvas.
+pub const MAX_CHUNK_SIZE: usize = 4096;
 
-        let n = re_sq + im_sq;
-        if n > THRESHOLD {
-            p.calculate(n, i);
-            return;
-        }
-        let re_im: i128 = (z.re as i128 * z.im as i128) >> 59;
-        z = Complex {
-            re: re_sq as i64 - im_sq as i64 + c.re,
-            im: re_im as i64 + c.im,
-        };
+/// Returns `z^2 + c`, or the squared magnitude of `z` if it is beyond the squared bailout
+/// radius `threshold`.
+#[inline(always)]
+pub(crate) fn step(z: Complex, c: Complex, threshold: i128) -> Result<Complex, i128> {
+    let re_sq = (z.re as i128).pow(2) >> 60;
+    let im_sq = (z.im as i128).pow(2) >> 60;
+
+    let n = re_sq + im_sq;
+    if n > threshold {
+        return Err(n);
     }
+    // Rounded towards zero, so that conjugate points iterate to exactly conjugate values.
+    let re_im = z.re as i128 * z.im as i128;
+    let re_im = if re_im < 0 {
+        -(-re_im >> 59)
+    } else {
+        re_im >> 59
+    };
+
+    Ok(Complex {
+        re: re_sq as i64 - im_sq as i64 + c.re,
+        im: re_im as i64 + c.im,
+    })
+}
+
+/// Iterates `z = z^2 + c` starting at `z0`. Points of the Mandelbrot set start at `z0 == c`.
+pub fn mandelbrot_val_at_point(
+    z0: Complex,
+    c: Complex,
+    max_iters: usize,
+    threshold: i128,
+    precision: Precision,
+    p: &mut PlotPoint,
+) {
+    iterate(precision, z0, c, 0, max_iters, threshold, p);
+}
 
-    p.calculate(0, max_iters);
+/// Continues iterating from `z`, the value after `first` iterations, in the representation
+/// `precision`.
+fn iterate(
+    precision: Precision,
+    z: Complex,
+    c: Complex,
+    first: usize,
+    max_iters: usize,
+    threshold: i128,
+    p: &mut PlotPoint,
+) {
+    match precision {
+        Precision::Float => precision::iterate::<Float>(z, c, first, max_iters, threshold, p),
+        Precision::Auto | Precision::Fixed => {
+            precision::iterate::<Fixed>(z, c, first, max_iters, threshold, p)
+        }
+        Precision::Arbitrary => {
+            precision::iterate::<Arbitrary>(z, c, first, max_iters, threshold, p)
+        }
+    }
 }
@@ -1,13 +1,97 @@
-use mandelbrot_web::Ctx;
-use leptos::html::Canvas;
+use leptos::html::{Canvas, Input};
+use leptos::leptos_dom::helpers::TimeoutHandle;
 use leptos::*;
+use mandelbrot_web::animation::{Easing, Keyframe, Timeline};
+use mandelbrot_web::bookmarks::{gallery, Bookmark, Bookmarks};
+use mandelbrot_web::export::{import_png, is_png, MAX_EXPORT_SIZE};
+use mandelbrot_web::fixed::{from_decimal, to_decimal};
+use mandelbrot_web::iterations::estimate_iters;
+use mandelbrot_web::navigation::{
+    default_view, key_command, pan, step_max_iters, zoom, History, KeyCommand, KEY_HELP,
+};
+use mandelbrot_web::orbit::{orbit_at, Orbit};
+use mandelbrot_web::params::ParamsFormat;
+use mandelbrot_web::settings::Settings;
+use mandelbrot_web::video::{zoom_path, IterRamp};
+use mandelbrot_web::{Complex, Ctx, Precision, RenderStrategy, View};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
 use wasm_bindgen::closure::Closure;
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{Clamped, JsValue};
+use wasm_bindgen_futures::JsFuture;
 use web_sys::{
-    js_sys::Array, wasm_bindgen::JsCast, Blob, BlobPropertyBag, MessageEvent, MouseEvent,
-    OffscreenCanvas, TouchEvent, Url, Worker,
+    js_sys::{Array, Uint8Array},
+    wasm_bindgen::JsCast,
+    Blob, BlobPropertyBag, CanvasRenderingContext2d, Element, HtmlAnchorElement, HtmlCanvasElement,
+    HtmlInputElement, ImageData, KeyboardEvent, MessageEvent, MouseEvent, OffscreenCanvas,
+    TouchEvent, Url, Worker,
 };
 
+/// Width of the animation preview, in pixels.
+const PREVIEW_WIDTH: usize = 160;
+
+/// Width of the gallery thumbnails, in pixels.
+const THUMBNAIL_WIDTH: usize = 96;
+
+/// Key of the saved bookmarks in the local storage.
+const BOOKMARKS_KEY: &str = "mandelbrot-bookmarks";
+
+/// Key of the saved settings in the local storage.
+const SETTINGS_KEY: &str = "mandelbrot-settings";
+
+/// Iteration limit of the Julia set thumbnail, which is redrawn while the cursor moves.
+const JULIA_PREVIEW_ITERS: usize = 200;
+
+/// Horizontal space taken by the controls panel and the margins around it, in CSS pixels.
+const CTRLS_WIDTH: f64 = 10.0 + 300.0 + 25.0;
+
+/// Time to wait for the window to stop changing size before the plot is resized.
+const RESIZE_DELAY: Duration = Duration::from_millis(200);
+
+/// How long the URL of a download is kept alive after the download is started.
+const REVOKE_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, PartialEq)]
+struct CanvasLayout {
+    css_width: f64,
+    css_height: f64,
+    pixel_ratio: f64,
+}
+
+impl CanvasLayout {
+    fn physical_width(&self) -> u32 {
+        (self.css_width * self.pixel_ratio).round().max(1.0) as u32
+    }
+
+    fn physical_height(&self) -> u32 {
+        (self.css_height * self.pixel_ratio).round().max(1.0) as u32
+    }
+
+    /// Height of an image `width` pixels wide with the aspect ratio of the canvas.
+    fn height_for(&self, width: usize) -> usize {
+        (width as u32 * self.physical_height() / self.physical_width()).max(1) as usize
+    }
+}
+
+fn canvas_layout() -> CanvasLayout {
+    let window = leptos::window();
+
+    let window_width = window.inner_width().unwrap().as_f64().unwrap();
+    let window_height = window.inner_height().unwrap().as_f64().unwrap();
+
+    CanvasLayout {
+        css_width: (window_width - CTRLS_WIDTH).floor().max(1.0),
+        css_height: window_height.floor().max(1.0),
+        pixel_ratio: window.device_pixel_ratio(),
+    }
+}
+
+/// Whether `size` can be used as the width or height of an export.
+fn valid_export_size(size: &usize) -> bool {
+    (1..=MAX_EXPORT_SIZE).contains(size)
+}
+
 fn worker_new(name: &str) -> Worker {
     let base = leptos::window().location().href().unwrap();
 
@@ -17,11 +101,10 @@ fn worker_new(name: &str) -> Worker {
             .into(),
     );
 
-    let blob = Blob::new_with_str_sequence_and_options(
-        &script,
-        BlobPropertyBag::new().type_("text/javascript"),
-    )
-    .unwrap();
+    let options = BlobPropertyBag::new();
+    options.set_type("text/javascript");
+
+    let blob = Blob::new_with_str_sequence_and_options(&script, &options).unwrap();
 
     let url = Url::create_object_url_with_blob(&blob).unwrap();
 
@@ -29,41 +112,138 @@ fn worker_new(name: &str) -> Worker {
 }
 
 fn main() {
-    _ = console_log::init_with_level(log::Level::Debug).unwrap();
+    console_log::init_with_level(log::Level::Debug).unwrap();
     console_error_panic_hook::set_once();
 
-    let window = leptos::window();
-
-    let window_width = window.inner_width().unwrap().as_f64().unwrap() as u32;
-    let window_height = window.inner_height().unwrap().as_f64().unwrap() as u32;
-
-    let canvas_width = window_width - 10 - 300 - 25;
-    let canvas_height = window_height;
+    let initial_layout = canvas_layout();
+    let canvas_width = initial_layout.physical_width();
+    let canvas_height = initial_layout.physical_height();
 
     let mut ctx = Ctx {
         win_width: canvas_width as usize,
         win_height: canvas_height as usize,
         ..Default::default()
     };
-    ctx.define_bounds(-922337203685477580, 10, 4035225266123964416);
+    load_settings().apply_to(&mut ctx);
 
     let worker = worker_new("worker");
 
     let canvas_node = create_node_ref::<Canvas>();
 
     let (ctx, set_ctx) = create_signal(ctx);
+    let (layout, set_layout) = create_signal(initial_layout);
     let (hidden, set_hidden) = create_signal(true);
-    let (x1, set_x1) = create_signal(0);
-    let (y1, set_y1) = create_signal(0);
+    let (x1, set_x1) = create_signal(0_i32);
+    let (y1, set_y1) = create_signal(0_i32);
     let (x2, set_x2) = create_signal(0);
     let (y2, set_y2) = create_signal(0);
+    let (export_width, set_export_width) = create_signal(canvas_width as usize);
+    let (export_height, set_export_height) = create_signal(canvas_height as usize);
+    let (job_progress, set_job_progress) = create_signal(None::<f64>);
+    let (zoom_start, set_zoom_start) = create_signal(None::<View>);
+    let (zoom_frames, set_zoom_frames) = create_signal(100_usize);
+    let (ramp_iters, set_ramp_iters) = create_signal(true);
+    let (timeline, set_timeline) = create_signal(Timeline::default());
+    let (fps, set_fps) = create_signal(30.0_f64);
+
+    let (orbit, set_orbit) = create_signal(None::<Orbit>);
+    let (show_orbit, set_show_orbit) = create_signal(true);
+    let (cursor, set_cursor) = create_signal(None::<(i64, i64)>);
+    let (view_error, set_view_error) = create_signal(false);
+    let (julia_preview, set_julia_preview) = create_signal(None::<Complex>);
+    let (bookmarks, set_bookmarks) = create_signal(load_bookmarks());
+    let (bookmark_name, set_bookmark_name) = create_signal(String::new());
+    let (params_format, set_params_format) = create_signal(ParamsFormat::Json);
+    let (params_error, set_params_error) = create_signal(None::<String>);
+    let (_, set_history) = create_signal(History::default());
+    let (show_help, set_show_help) = create_signal(false);
+    let (tuning_error, set_tuning_error) = create_signal(None::<String>);
+
+    let preview_node = create_node_ref::<Canvas>();
+    let orbit_node = create_node_ref::<Canvas>();
+    let julia_node = create_node_ref::<Canvas>();
+    let center_re_node = create_node_ref::<Input>();
+    let center_im_node = create_node_ref::<Input>();
+    let span_node = create_node_ref::<Input>();
+    let preview_height = move || layout.get().height_for(PREVIEW_WIDTH);
+
+    // Renders the Julia set thumbnail. It takes one request at a time, and only the latest
+    // cursor position is kept while it is busy.
+    let julia_worker = worker_new("worker");
+    let julia_idle = Rc::new(Cell::new(false));
+    let julia_pending = Rc::new(Cell::new(None::<Complex>));
+
+    let request_julia_preview = {
+        let julia_worker = julia_worker.clone();
+        let julia_idle = julia_idle.clone();
+        let julia_pending = julia_pending.clone();
+
+        move || {
+            if !julia_idle.get() {
+                return;
+            }
+            let Some(c) = julia_pending.take() else {
+                return;
+            };
+
+            let preview_height = layout.get_untracked().height_for(PREVIEW_WIDTH);
+            let Ok(mut preview_ctx) = ctx.get_untracked().resized(PREVIEW_WIDTH, preview_height)
+            else {
+                return;
+            };
+            preview_ctx.julia = Some(c);
+            preview_ctx.set_view(&View {
+                max_iters: preview_ctx.max_iters.min(JULIA_PREVIEW_ITERS),
+                ..View::julia()
+            });
+
+            julia_idle.set(false);
+            set_julia_preview.update(|v| *v = Some(c));
+            let _ = julia_worker.post_message(&pack_frames_message(&[preview_ctx], true));
+        }
+    };
+
+    let request_clone = request_julia_preview.clone();
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::wrap(Box::new(move |e: MessageEvent| {
+        let data = Array::from(&e.data());
+
+        match data.get(0).as_string().as_deref() {
+            Some("frame") => {
+                let rgba = Uint8Array::from(data.get(2)).to_vec();
+                let image =
+                    ImageData::new_with_u8_clamped_array(Clamped(&rgba), PREVIEW_WIDTH as u32)
+                        .unwrap();
+
+                if let Some(canvas) = julia_node.get_untracked() {
+                    let context: CanvasRenderingContext2d =
+                        canvas.get_context("2d").unwrap().unwrap().unchecked_into();
+                    context.put_image_data(&image, 0.0, 0.0).unwrap();
+                }
+            }
+            Some("progress") => {}
+            // Either the worker just started or it finished the last thumbnail.
+            _ => {
+                julia_idle.set(true);
+                request_clone();
+            }
+        }
+    }));
+    julia_worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
 
     let brightness = move || ctx.get().brightness;
+    let palette_offset = move || ctx.get().palette_offset;
     let iters = move || ctx.get().max_iters;
+    let auto_iters = move || ctx.get().auto_iters;
+    let progressive = move || ctx.get().progressive;
     let x = move || x1.get().min(x2.get()) as usize;
-    let w = move || (x1.get() as i32 - x2.get()).abs() as usize;
+    let w = move || (x1.get() - x2.get()).unsigned_abs() as usize;
     let y = move || y1.get().min(y2.get()) as usize;
-    let h = move || (y1.get() as i32 - y2.get()).abs() as usize;
+    let h = move || (y1.get() - y2.get()).unsigned_abs() as usize;
+    let css_width = move || format!("{}px", layout.get().css_width);
+    let css_height = move || format!("{}px", layout.get().css_height);
+    // The selection is tracked in CSS pixels, the plot in physical ones.
+    let physical = move |v: usize| (v as f64 * layout.get_untracked().pixel_ratio).round() as usize;
 
     let worker_clone = worker.clone();
     create_effect(move |_| {
@@ -101,6 +281,39 @@ fn main() {
         onmessage.forget();
     });
 
+    // Resizing produces a burst of events, only the last one is acted on.
+    let resize_timeout = Rc::new(Cell::new(None::<TimeoutHandle>));
+    let worker_clone = worker.clone();
+    window_event_listener(ev::resize, move |_| {
+        if let Some(handle) = resize_timeout.take() {
+            handle.clear();
+        }
+
+        let worker_clone = worker_clone.clone();
+        let handle = set_timeout_with_handle(
+            move || {
+                let new_layout = canvas_layout();
+                if new_layout == layout.get_untracked() {
+                    return;
+                }
+
+                let mut ctx = ctx.get_untracked();
+                ctx.resize(
+                    new_layout.physical_width() as usize,
+                    new_layout.physical_height() as usize,
+                );
+                ctx.reset_min_max();
+
+                set_layout.update(|v| *v = new_layout);
+                set_ctx.update(|v| *v = ctx);
+
+                let _ = worker_clone.post_message(&pack_resize_message(&ctx));
+            },
+            RESIZE_DELAY,
+        );
+        resize_timeout.set(handle.ok());
+    });
+
     let omd = move |e: MouseEvent| {
         if e.button() != 0 {
             return;
@@ -111,9 +324,131 @@ fn main() {
         set_y1.update(|v| *v = e.offset_y());
     };
 
+    // The orbit can take as long as a render of a point, so it is computed at most once per
+    // frame, for the latest position of the cursor.
+    let orbit_pending = Rc::new(Cell::new(None::<(usize, usize)>));
+    let request_orbit = move |x: usize, y: usize| {
+        if orbit_pending.replace(Some((x, y))).is_some() {
+            return;
+        }
+
+        let orbit_pending = orbit_pending.clone();
+        request_animation_frame(move || {
+            let Some((x, y)) = orbit_pending.take() else {
+                return;
+            };
+
+            // The cursor may have left the canvas since.
+            if show_orbit.get_untracked() && cursor.get_untracked().is_some() {
+                set_orbit.update(|v| *v = Some(orbit_at(&ctx.get_untracked(), x, y)));
+            }
+        });
+    };
+
     let omm = move |e: MouseEvent| {
         set_x2.update(|v| *v = e.offset_x());
         set_y2.update(|v| *v = e.offset_y());
+
+        let ctx = ctx.get_untracked();
+        let x = physical(e.offset_x().max(0) as usize).min(ctx.win_width - 1);
+        let y = physical(e.offset_y().max(0) as usize).min(ctx.win_height - 1);
+
+        set_cursor.update(|v| *v = Some(ctx.get_coords(x, y)));
+
+        if ctx.julia.is_none() {
+            let (re, im) = ctx.get_coords(x, y);
+            julia_pending.set(Some(Complex::new(re, im)));
+            request_julia_preview();
+        }
+
+        if show_orbit.get_untracked() {
+            request_orbit(x, y);
+        }
+    };
+
+    let cursor_re = move || cursor.get().map(|(re, _)| to_decimal(re));
+    let cursor_im = move || cursor.get().map(|(_, im)| to_decimal(im));
+    let center_re = move || to_decimal(ctx.get().view().center_x);
+    let center_im = move || to_decimal(ctx.get().view().center_y);
+    let span = move || to_decimal(ctx.get().view().horizontal_span);
+    let magnification = move || format!("{:.3e}", ctx.get().view().magnification());
+
+    let worker_clone = worker.clone();
+    let set_julia = move |julia: Option<Complex>| {
+        let mut ctx = ctx.get_untracked();
+
+        ctx.julia = julia;
+        ctx.set_view(&View {
+            max_iters: ctx.max_iters,
+            ..if julia.is_some() {
+                View::julia()
+            } else {
+                View::default()
+            }
+        });
+        ctx.reset_min_max();
+
+        set_ctx.update(|v| *v = ctx);
+
+        let _ = worker_clone.post_message(&pack_message(&ctx));
+    };
+    let set_mandelbrot = set_julia.clone();
+    let is_julia = move || ctx.get().julia.is_some();
+
+    let worker_clone = worker.clone();
+    let on_go_click = move |_| {
+        let value = |node: NodeRef<Input>| from_decimal(&node.get_untracked().unwrap().value());
+        let mut ctx = ctx.get_untracked();
+
+        let view = match (
+            value(center_re_node),
+            value(center_im_node),
+            value(span_node),
+        ) {
+            (Some(center_x), Some(center_y), Some(horizontal_span)) if horizontal_span > 0 => {
+                View {
+                    center_x,
+                    center_y,
+                    horizontal_span,
+                    max_iters: ctx.max_iters,
+                }
+            }
+            _ => {
+                set_view_error.update(|v| *v = true);
+                return;
+            }
+        };
+
+        set_view_error.update(|v| *v = false);
+        set_history.update(|v| v.push(ctx.view()));
+
+        ctx.set_view(&view);
+        ctx.reset_min_max();
+
+        set_ctx.update(|v| *v = ctx);
+
+        let _ = worker_clone.post_message(&pack_message(&ctx));
+    };
+
+    create_effect(move |_| {
+        let orbit = orbit.get();
+        let layout = layout.get();
+
+        if let Some(canvas) = orbit_node.get() {
+            draw_orbit(&canvas, &ctx.get_untracked(), layout, orbit.as_ref());
+        }
+    });
+
+    let orbit_info = move || {
+        orbit.get().map(|orbit| {
+            let escape = orbit.escape.map_or("never".to_string(), |i| i.to_string());
+            let period = orbit.period.map_or("-".to_string(), |p| p.to_string());
+
+            format!(
+                "{} iterations, escapes {escape}, period {period}",
+                orbit.iterations
+            )
+        })
     };
 
     let worker_clone = worker.clone();
@@ -123,8 +458,9 @@ fn main() {
         }
 
         let mut ctx = ctx.get();
+        set_history.update(|v| v.push(ctx.view()));
 
-        ctx.define_bounds_from(x(), y(), w(), h());
+        ctx.define_bounds_from(physical(x()), physical(y()), physical(w()), physical(h()));
         ctx.reset_min_max();
         ctx.needs_recalc = true;
 
@@ -154,8 +490,9 @@ fn main() {
     let worker_clone = worker.clone();
     let ote = move |_| {
         let mut ctx = ctx.get();
+        set_history.update(|v| v.push(ctx.view()));
 
-        ctx.define_bounds_from(x(), y(), w(), h());
+        ctx.define_bounds_from(physical(x()), physical(y()), physical(w()), physical(h()));
         ctx.reset_min_max();
         ctx.needs_recalc = true;
 
@@ -172,14 +509,608 @@ fn main() {
         let _ = worker_clone.post_message(&pack_message(&ctx));
     };
 
+    // Exports are rendered from scratch on a job worker, so that they never show a plot that
+    // is still being computed and don't hold up the view.
+    let export_image = move || {
+        if job_progress.get_untracked().is_some() {
+            return;
+        }
+
+        let Ok(export_ctx) = ctx
+            .get_untracked()
+            .resized(export_width.get_untracked(), export_height.get_untracked())
+        else {
+            return;
+        };
+        set_job_progress.update(|v| *v = Some(0.0));
+
+        run_job(pack_export_message(&export_ctx), move |data| {
+            if data.get(0).as_string().as_deref() != Some("export") {
+                return false;
+            }
+
+            download(&data.get(1), "mandelbrot.png", "image/png");
+            set_job_progress.update(|v| *v = None);
+            true
+        });
+    };
+
+    let on_export_click = move |_| export_image();
+
+    // Saves the settings whenever they change, which is not on every message of the worker.
+    create_effect(move |saved: Option<Settings>| {
+        let settings = Settings::from_ctx(&ctx.get());
+        if saved.as_ref() != Some(&settings) {
+            store_settings(&settings);
+        }
+
+        settings
+    });
+
+    let worker_clone = worker.clone();
+    let on_reset_settings_click = move |_| {
+        let mut ctx = ctx.get_untracked();
+        set_history.update(|v| v.push(ctx.view()));
+
+        Settings::default().apply_to(&mut ctx);
+        ctx.reset_min_max();
+
+        set_ctx.update(|v| *v = ctx);
+
+        let _ = worker_clone.post_message(&pack_message(&ctx));
+    };
+
+    // Applies a change of the advanced settings if the changed context is valid. Only changes
+    // that alter the computed points start a new render.
+    let worker_clone = worker.clone();
+    let change_tuning = move |changed: Result<Ctx, String>, recalc: bool| {
+        let mut ctx = match changed.and_then(|ctx| ctx.validate().map(|_| ctx)) {
+            Ok(ctx) => ctx,
+            Err(err) => {
+                set_tuning_error.update(|v| *v = Some(err));
+                return;
+            }
+        };
+        set_tuning_error.update(|v| *v = None);
+
+        if recalc {
+            ctx.reset_min_max();
+        }
+        set_ctx.update(|v| *v = ctx);
+
+        if recalc {
+            let _ = worker_clone.post_message(&pack_message(&ctx));
+        }
+    };
+
+    let strategy_options = RenderStrategy::ALL
+        .into_iter()
+        .map(|strategy| {
+            view! {
+                <option value=strategy.name() selected=move || ctx.get().strategy == strategy>
+                    {strategy.name()}
+                </option>
+            }
+        })
+        .collect_view();
+    let precision_options = Precision::ALL
+        .into_iter()
+        .map(|precision| {
+            view! {
+                <option value=precision.name() selected=move || ctx.get().precision == precision>
+                    {precision.name()}
+                </option>
+            }
+        })
+        .collect_view();
+
+    let change_chunk_size = change_tuning.clone();
+    let change_bailout = change_tuning.clone();
+    let change_strategy = change_tuning.clone();
+    let change_symmetry = change_tuning.clone();
+    let change_precision = change_tuning;
+
+    let worker_clone = worker.clone();
+    window_event_listener(ev::keydown, move |e: KeyboardEvent| {
+        // Typing into the controls is left alone.
+        let target = e.target().and_then(|t| t.dyn_into::<Element>().ok());
+        if target.is_some_and(|t| matches!(t.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA"))
+        {
+            return;
+        }
+        if e.key() == "Escape" {
+            set_show_help.update(|v| *v = false);
+            return;
+        }
+
+        let Some(command) = key_command(&e.key(), e.ctrl_key() || e.meta_key()) else {
+            return;
+        };
+        e.prevent_default();
+
+        let mut ctx = ctx.get_untracked();
+        let view = match command {
+            KeyCommand::Pan { x, y } => pan(&ctx, x, y),
+            KeyCommand::ZoomIn => zoom(&ctx, true),
+            KeyCommand::ZoomOut => zoom(&ctx, false),
+            KeyCommand::Reset => default_view(&ctx),
+            KeyCommand::Undo => {
+                let mut previous = None;
+                set_history.update(|v| previous = v.pop());
+
+                match previous {
+                    Some(view) => view,
+                    None => return,
+                }
+            }
+            KeyCommand::MoreIters | KeyCommand::FewerIters => {
+                let Some(ctx) = step_max_iters(&ctx, command == KeyCommand::MoreIters) else {
+                    return;
+                };
+                set_ctx.update(|v| *v = ctx);
+
+                let _ = worker_clone.post_message(&pack_message(&ctx));
+                return;
+            }
+            KeyCommand::SaveImage => {
+                export_image();
+                return;
+            }
+            KeyCommand::ToggleHelp => {
+                set_show_help.update(|v| *v = !*v);
+                return;
+            }
+        };
+
+        if command != KeyCommand::Undo {
+            set_history.update(|v| v.push(ctx.view()));
+        }
+
+        ctx.set_view(&view);
+        ctx.reset_min_max();
+
+        set_ctx.update(|v| *v = ctx);
+
+        let _ = worker_clone.post_message(&pack_message(&ctx));
+    });
+
+    let help = KEY_HELP
+        .into_iter()
+        .map(|(key, action)| {
+            view! {
+                <div><kbd>{key}</kbd>" "{action}</div>
+            }
+        })
+        .collect_view();
+
+    let on_poster_click = move |_| {
+        if job_progress.get_untracked().is_some() {
+            return;
+        }
+
+        let Ok(poster_ctx) = ctx.get().resized(export_width.get(), export_height.get()) else {
+            return;
+        };
+        set_job_progress.update(|v| *v = Some(0.0));
+
+        run_job(pack_poster_message(&poster_ctx), move |data| {
+            match data.get(0).as_string().as_deref() {
+                Some("progress") => {
+                    let progress = data.get(1).as_f64().unwrap();
+                    set_job_progress.update(|v| *v = Some(progress));
+                }
+                Some("poster") => {
+                    download(&data.get(1), "mandelbrot-poster.png", "image/png");
+                    set_job_progress.update(|v| *v = None);
+                    return true;
+                }
+                _ => {}
+            }
+
+            false
+        });
+    };
+
+    let on_zoom_start_click = move |_| {
+        set_zoom_start.update(|v| *v = Some(ctx.get().view()));
+    };
+
+    let export_frames = move |frames: Vec<Ctx>| {
+        set_job_progress.update(|v| *v = Some(0.0));
+
+        run_job(pack_frames_message(&frames, false), move |data| {
+            match data.get(0).as_string().as_deref() {
+                Some("progress") => {
+                    let progress = data.get(1).as_f64().unwrap();
+                    set_job_progress.update(|v| *v = Some(progress));
+                }
+                Some("done") => {
+                    download(&data.get(1), "mandelbrot-frames.tar", "application/x-tar");
+                    set_job_progress.update(|v| *v = None);
+                    return true;
+                }
+                _ => {}
+            }
+
+            false
+        });
+    };
+
+    let on_zoom_video_click = move |_| {
+        if job_progress.get_untracked().is_some() {
+            return;
+        }
+
+        let ctx = ctx.get();
+        let Ok(frame_ctx) = ctx.resized(export_width.get(), export_height.get()) else {
+            return;
+        };
+        let start = zoom_start.get().unwrap_or_default();
+        let ramp = if ramp_iters.get() {
+            IterRamp::Depth
+        } else {
+            IterRamp::Constant
+        };
+
+        // Ramp towards the limit the end depth needs, even if the view still uses the start's.
+        let end = View {
+            max_iters: ctx.max_iters.max(estimate_iters(&ctx.view())),
+            ..ctx.view()
+        };
+
+        let frames: Vec<Ctx> = zoom_path(&start, &end, zoom_frames.get(), ramp)
+            .iter()
+            .map(|view| {
+                let mut frame_ctx = frame_ctx;
+                frame_ctx.set_view(view);
+                frame_ctx
+            })
+            .collect();
+
+        export_frames(frames);
+    };
+
+    let on_add_keyframe_click = move |_| {
+        set_timeline.update(|timeline| {
+            let time = if timeline.keyframes().is_empty() {
+                0.0
+            } else {
+                timeline.duration() + 2.0
+            };
+            timeline.add(Keyframe::from_ctx(&ctx.get_untracked(), time));
+        });
+    };
+
+    let worker_clone = worker.clone();
+    let go_to_keyframe = move |keyframe: Keyframe| {
+        let mut ctx = ctx.get_untracked();
+
+        keyframe.apply_to(&mut ctx);
+        ctx.reset_min_max();
+
+        set_ctx.update(|v| *v = ctx);
+
+        let _ = worker_clone.post_message(&pack_message(&ctx));
+    };
+
+    let worker_clone = worker.clone();
+    let go_to_bookmark = move |bookmark: &Bookmark| {
+        let mut ctx = ctx.get_untracked();
+        set_history.update(|v| v.push(ctx.view()));
+
+        bookmark.apply_to(&mut ctx);
+        ctx.reset_min_max();
+
+        set_ctx.update(|v| *v = ctx);
+
+        let _ = worker_clone.post_message(&pack_message(&ctx));
+    };
+
+    let on_save_bookmark_click = move |_| {
+        let name = bookmark_name.get_untracked();
+        if name.trim().is_empty() {
+            return;
+        }
+
+        set_bookmarks.update(|v| {
+            v.save(Bookmark::from_ctx(name.trim(), &ctx.get_untracked()));
+            store_bookmarks(v);
+        });
+        set_bookmark_name.update(|v| v.clear());
+    };
+
+    let on_export_params_click = move |_| {
+        let format = params_format.get_untracked();
+        let text = format.export(&ctx.get_untracked());
+
+        download(
+            &JsValue::from_str(&text),
+            &format!("mandelbrot.{}", format.extension()),
+            "text/plain",
+        );
+    };
+
+    let worker_clone = worker.clone();
+    let on_import_params = move |ev: ev::Event| {
+        let input: HtmlInputElement = event_target(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        // Lets the same file be picked again.
+        input.set_value("");
+
+        let worker = worker_clone.clone();
+        spawn_local(async move {
+            let ctx_before = ctx.get_untracked();
+            let mut ctx = ctx_before;
+
+            let result = if is_png(&file.name()) {
+                let png = JsFuture::from(file.array_buffer())
+                    .await
+                    .map(|v| Uint8Array::new(&v).to_vec())
+                    .unwrap_or_default();
+                import_png(&png, &mut ctx)
+            } else {
+                let text = JsFuture::from(file.text())
+                    .await
+                    .ok()
+                    .and_then(|v| v.as_string())
+                    .unwrap_or_default();
+                ParamsFormat::from_file_name(&file.name())
+                    .ok_or_else(|| "Unknown file format.".to_string())
+                    .and_then(|format| format.import(&text, &mut ctx))
+            };
+            if let Err(err) = result {
+                set_params_error.update(|v| *v = Some(err));
+                return;
+            }
+
+            set_params_error.update(|v| *v = None);
+            set_history.update(|v| v.push(ctx_before.view()));
+            ctx.reset_min_max();
+
+            set_ctx.update(|v| *v = ctx);
+
+            let _ = worker.post_message(&pack_message(&ctx));
+        });
+    };
+
+    let params_format_options = ParamsFormat::ALL
+        .into_iter()
+        .map(|format| {
+            view! {
+                <option value=format.extension() selected=move || params_format.get() == format>
+                    {format.extension()}
+                </option>
+            }
+        })
+        .collect_view();
+
+    let go_to_bookmark_clone = go_to_bookmark.clone();
+    let bookmark_list = move || {
+        bookmarks
+            .get()
+            .bookmarks()
+            .iter()
+            .map(|bookmark| {
+                let bookmark = bookmark.clone();
+                let name = bookmark.name.clone();
+                let go_to_bookmark = go_to_bookmark_clone.clone();
+
+                view! {
+                    <div class="bookmark">
+                        <button on:click=move |_| go_to_bookmark(&bookmark)>{name.clone()}</button>
+                        <button on:click=move |_| set_bookmarks.update(|v| {
+                            v.remove(&name);
+                            store_bookmarks(v);
+                        })>x</button>
+                    </div>
+                }
+            })
+            .collect_view()
+    };
+
+    let gallery = gallery();
+    let thumbnail_height = move || layout.get().height_for(THUMBNAIL_WIDTH);
+    let thumbnail_nodes: Vec<_> = gallery
+        .iter()
+        .map(|_| create_node_ref::<Canvas>())
+        .collect();
+
+    // Renders the thumbnails again whenever the canvas changes its aspect ratio.
+    let gallery_clone = gallery.clone();
+    let nodes_clone = thumbnail_nodes.clone();
+    create_effect(move |_| {
+        let height = thumbnail_height();
+        let thumbnail_frames: Vec<Ctx> = gallery_clone
+            .iter()
+            .filter_map(|bookmark| {
+                let mut thumbnail_ctx =
+                    ctx.get_untracked().resized(THUMBNAIL_WIDTH, height).ok()?;
+                bookmark.apply_to(&mut thumbnail_ctx);
+                Some(thumbnail_ctx)
+            })
+            .collect();
+
+        let nodes = nodes_clone.clone();
+        run_job(
+            pack_frames_message(&thumbnail_frames, true),
+            move |data| match data.get(0).as_string().as_deref() {
+                Some("frame") => {
+                    let i = data.get(1).as_f64().unwrap() as usize;
+                    let rgba = Uint8Array::from(data.get(2)).to_vec();
+                    let image = ImageData::new_with_u8_clamped_array(
+                        Clamped(&rgba),
+                        THUMBNAIL_WIDTH as u32,
+                    )
+                    .unwrap();
+
+                    if let Some(canvas) = nodes[i].get_untracked() {
+                        let context: CanvasRenderingContext2d =
+                            canvas.get_context("2d").unwrap().unwrap().unchecked_into();
+                        context.put_image_data(&image, 0.0, 0.0).unwrap();
+                    }
+                    false
+                }
+                Some("done") => true,
+                _ => false,
+            },
+        );
+    });
+
+    let gallery_list = gallery
+        .into_iter()
+        .zip(thumbnail_nodes)
+        .map(|(bookmark, node)| {
+            let name = bookmark.name.clone();
+            let go_to_bookmark = go_to_bookmark.clone();
+
+            view! {
+                <div class="thumbnail" title=name.clone() on:click=move |_| go_to_bookmark(&bookmark)>
+                    <canvas _ref=node width=THUMBNAIL_WIDTH height=thumbnail_height></canvas>
+                    <div>{name}</div>
+                </div>
+            }
+        })
+        .collect_view();
+
+    let keyframes = move || {
+        timeline
+            .get()
+            .keyframes()
+            .iter()
+            .enumerate()
+            .map(|(i, keyframe)| {
+                let keyframe = *keyframe;
+                let go_to_keyframe = go_to_keyframe.clone();
+
+                view! {
+                    <div class="keyframe">
+                        <input type="number" step="0.1" value=keyframe.time on:change=move |ev| {
+                            let parsed_v = event_target_value(&ev).parse();
+                            if let Ok(parsed_v) = parsed_v {
+                                set_timeline.update(|v| v.update(i, Keyframe { time: parsed_v, ..keyframe }))
+                            }
+                        } />
+                        <select on:change=move |ev| {
+                            let parsed_v = event_target_value(&ev).parse::<usize>();
+                            if let Ok(parsed_v) = parsed_v {
+                                let easing = Easing::ALL[parsed_v];
+                                set_timeline.update(|v| v.update(i, Keyframe { easing, ..keyframe }))
+                            }
+                        }>
+                            {Easing::ALL
+                                .iter()
+                                .enumerate()
+                                .map(|(j, easing)| view! {
+                                    <option value=j selected=*easing == keyframe.easing>{easing.name()}</option>
+                                })
+                                .collect_view()}
+                        </select>
+                        <button on:click=move |_| go_to_keyframe(keyframe)>Go</button>
+                        <button on:click=move |_| set_timeline.update(|v| { v.remove(i); })>x</button>
+                    </div>
+                }
+            })
+            .collect_view()
+    };
+
+    let on_preview_click = move |_| {
+        if job_progress.get_untracked().is_some() {
+            return;
+        }
+
+        let preview_height = layout.get_untracked().height_for(PREVIEW_WIDTH);
+        let Ok(preview_ctx) = ctx.get().resized(PREVIEW_WIDTH, preview_height) else {
+            return;
+        };
+        let frames = timeline.get().frames(&preview_ctx, fps.get());
+        let frame_duration = Duration::from_secs_f64(1.0 / fps.get());
+
+        set_job_progress.update(|v| *v = Some(0.0));
+
+        let mut images = Vec::new();
+        run_job(pack_frames_message(&frames, true), move |data| {
+            match data.get(0).as_string().as_deref() {
+                Some("progress") => {
+                    let progress = data.get(1).as_f64().unwrap();
+                    set_job_progress.update(|v| *v = Some(progress));
+                }
+                Some("frame") => {
+                    let rgba = Uint8Array::from(data.get(2)).to_vec();
+                    images.push(
+                        ImageData::new_with_u8_clamped_array(Clamped(&rgba), PREVIEW_WIDTH as u32)
+                            .unwrap(),
+                    );
+                }
+                Some("done") => {
+                    set_job_progress.update(|v| *v = None);
+
+                    let context: CanvasRenderingContext2d = preview_node
+                        .get_untracked()
+                        .unwrap()
+                        .get_context("2d")
+                        .unwrap()
+                        .unwrap()
+                        .unchecked_into();
+
+                    for (i, image) in images.drain(..).enumerate() {
+                        let context = context.clone();
+                        set_timeout(
+                            move || context.put_image_data(&image, 0.0, 0.0).unwrap(),
+                            frame_duration * i as u32,
+                        );
+                    }
+
+                    return true;
+                }
+                _ => {}
+            }
+
+            false
+        });
+    };
+
+    let on_export_animation_click = move |_| {
+        if job_progress.get_untracked().is_some() {
+            return;
+        }
+
+        let Ok(frame_ctx) = ctx.get().resized(export_width.get(), export_height.get()) else {
+            return;
+        };
+        export_frames(timeline.get().frames(&frame_ctx, fps.get()));
+    };
+
+    let job_status = move || {
+        job_progress
+            .get()
+            .map(|p| format!("{:.0}%", p * 100.0))
+            .unwrap_or_default()
+    };
+
     let container = leptos::document().get_element_by_id("main").unwrap();
     mount_to(container.unchecked_into(), move || {
         view! {
-            <div id="canv" on:mousedown=omd on:mousemove=omm on:mouseup=omu on:touchstart=ots on:touchmove=otm on:touchend=ote>
+            <div id="canv" on:mousedown=omd on:mousemove=omm on:mouseleave=move |_| {
+                set_orbit.update(|v| *v = None);
+                set_cursor.update(|v| *v = None);
+            } on:mouseup=omu on:touchstart=ots on:touchmove=otm on:touchend=ote>
                 <div id="selection" hidden=hidden style:left=x style:top=y style:width=w style:height=h />
-                <canvas _ref=canvas_node></canvas>
+                <canvas _ref=canvas_node style:width=css_width style:height=css_height></canvas>
+                <div id="help" hidden=move || !show_help.get()>{help}</div>
+                <canvas id="orbit" _ref=orbit_node width=move || layout.get().physical_width() height=move || layout.get().physical_height() style:width=css_width style:height=css_height></canvas>
             </div>
             <div id="ctrls">
+                <div class="readout">
+                    <div><label>Cursor</label>{cursor_re}<br />{cursor_im}</div>
+                    <div><label>Center</label><input _ref=center_re_node prop:value=center_re /></div>
+                    <div><label>i</label><input _ref=center_im_node prop:value=center_im /></div>
+                    <div><label>Span</label><input _ref=span_node prop:value=span /></div>
+                    <div><label>Zoom</label>{magnification}"x"</div>
+                    <button on:click=on_go_click>Go</button>
+                    <span hidden=move || !view_error.get()>" Invalid view"</span>
+                </div>
                 <div>
                     <label>Brightness</label><input type="number" value=brightness on:input=move |ev| {
                         let parsed_v = event_target_value(&ev).parse();
@@ -189,7 +1120,15 @@ fn main() {
                     } />
                 </div>
                 <div>
-                    <label>Iters</label><input type="number" value=iters on:input=move |ev| {
+                    <label>Palette offset</label><input type="number" step="0.05" value=palette_offset on:input=move |ev| {
+                        let parsed_v = event_target_value(&ev).parse();
+                        if let Ok(parsed_v) = parsed_v {
+                            set_ctx.update(|v| v.palette_offset = parsed_v)
+                        }
+                    } />
+                </div>
+                <div>
+                    <label>Iters</label><input type="number" value=iters disabled=auto_iters on:input=move |ev| {
                         let parsed_v = event_target_value(&ev).parse();
                         if let Ok(parsed_v) = parsed_v {
                             set_ctx.update(|v| {
@@ -198,13 +1137,231 @@ fn main() {
                             })
                         }
                     } />
+                    <label>Auto</label><input type="checkbox" checked=auto_iters on:change=move |ev| {
+                        set_ctx.update(|v| {
+                            v.needs_recalc = true;
+                            v.auto_iters = event_target_checked(&ev);
+                        })
+                    } />
+                </div>
+                <div>
+                    <label>Progressive</label><input type="checkbox" checked=progressive on:change=move |ev| {
+                        set_ctx.update(|v| v.progressive = event_target_checked(&ev))
+                    } />
+                </div>
+                <div class="julia">
+                    <label>Julia</label>{move || julia_preview.get().map(|c| format!("{:.6} {:+.6}i", c.re as f64 / (1_u64 << 60) as f64, c.im as f64 / (1_u64 << 60) as f64))}
+                    <canvas _ref=julia_node width=PREVIEW_WIDTH height=preview_height hidden=is_julia on:click=move |_| {
+                        if let Some(c) = julia_preview.get_untracked() {
+                            set_julia(Some(c));
+                        }
+                    }></canvas>
+                    <button hidden=move || !is_julia() on:click=move |_| set_mandelbrot(None)>Mandelbrot</button>
+                </div>
+                <div>
+                    <label>Orbit</label><input type="checkbox" checked=show_orbit on:change=move |ev| {
+                        set_show_orbit.update(|v| *v = event_target_checked(&ev))
+                    } />
+                    <div>{orbit_info}</div>
                 </div>
                 <button on:click=on_update_click>Update</button>
+                <details class="advanced">
+                    <summary>Advanced</summary>
+                    <div>
+                        <label>Chunk size</label><input type="number" min="1" prop:value=move || ctx.get().chunk_size on:change=move |ev| {
+                            let change = event_target_value(&ev)
+                                .parse()
+                                .map(|chunk_size| Ctx { chunk_size, ..ctx.get_untracked() })
+                                .map_err(|_| "Invalid chunk size.".to_string());
+                            change_chunk_size(change, false)
+                        } />
+                    </div>
+                    <div>
+                        <label>Bailout</label><input type="number" step="0.1" prop:value=move || ctx.get().bailout on:change=move |ev| {
+                            let change = event_target_value(&ev)
+                                .parse()
+                                .map(|bailout| Ctx { bailout, ..ctx.get_untracked() })
+                                .map_err(|_| "Invalid bailout radius.".to_string());
+                            change_bailout(change, true)
+                        } />
+                    </div>
+                    <div>
+                        <label>Strategy</label><select on:change=move |ev| {
+                            let change = RenderStrategy::from_name(&event_target_value(&ev))
+                                .map(|strategy| Ctx { strategy, ..ctx.get_untracked() })
+                                .ok_or_else(|| "Invalid strategy.".to_string());
+                            change_strategy(change, true)
+                        }>
+                            {strategy_options}
+                        </select>
+                    </div>
+                    <div>
+                        <label>Symmetry</label><input type="checkbox" prop:checked=move || ctx.get().symmetry on:change=move |ev| {
+                            let symmetry = event_target_checked(&ev);
+                            change_symmetry(Ok(Ctx { symmetry, ..ctx.get_untracked() }), true)
+                        } />
+                    </div>
+                    <div>
+                        <label>Precision</label><select on:change=move |ev| {
+                            let change = Precision::from_name(&event_target_value(&ev))
+                                .map(|precision| Ctx { precision, ..ctx.get_untracked() })
+                                .ok_or_else(|| "Invalid precision.".to_string());
+                            change_precision(change, true)
+                        }>
+                            {precision_options}
+                        </select>
+                        <span>" "{move || ctx.get().backend().name()}</span>
+                    </div>
+                    <span hidden=move || tuning_error.get().is_none()>{move || tuning_error.get()}</span>
+                </details>
+                <button on:click=on_reset_settings_click>Reset to defaults</button>
+                <button title="Keyboard shortcuts" on:click=move |_| set_show_help.update(|v| *v = !*v)>?</button>
+                <div>
+                    <label>Export</label><input type="number" min="1" max=MAX_EXPORT_SIZE value=export_width on:input=move |ev| {
+                        let parsed_v = event_target_value(&ev).parse().ok().filter(valid_export_size);
+                        if let Some(parsed_v) = parsed_v {
+                            set_export_width.update(|v| *v = parsed_v)
+                        }
+                    } />
+                    <label>x</label><input type="number" min="1" max=MAX_EXPORT_SIZE value=export_height on:input=move |ev| {
+                        let parsed_v = event_target_value(&ev).parse().ok().filter(valid_export_size);
+                        if let Some(parsed_v) = parsed_v {
+                            set_export_height.update(|v| *v = parsed_v)
+                        }
+                    } />
+                </div>
+                <button on:click=on_export_click>Export</button>
+                <button on:click=on_poster_click>Poster</button>
+                <div>
+                    <label>Frames</label><input type="number" value=zoom_frames on:input=move |ev| {
+                        let parsed_v = event_target_value(&ev).parse();
+                        if let Ok(parsed_v) = parsed_v {
+                            set_zoom_frames.update(|v| *v = parsed_v)
+                        }
+                    } />
+                </div>
+                <div>
+                    <label>Ramp iters</label><input type="checkbox" checked=ramp_iters on:change=move |ev| {
+                        set_ramp_iters.update(|v| *v = event_target_checked(&ev))
+                    } />
+                </div>
+                <button on:click=on_zoom_start_click>Zoom start</button>
+                <button on:click=on_zoom_video_click>Zoom video</button>
+                <div class="bookmarks">
+                    <label>Bookmarks</label>
+                    {bookmark_list}
+                    <input type="text" prop:value=bookmark_name on:input=move |ev| {
+                        set_bookmark_name.update(|v| *v = event_target_value(&ev))
+                    } />
+                    <button on:click=on_save_bookmark_click>Save</button>
+                </div>
+                <div class="params">
+                    <label>Parameters</label>
+                    <select on:change=move |ev| {
+                        let format = ParamsFormat::from_file_name(&format!(".{}", event_target_value(&ev)));
+                        if let Some(format) = format {
+                            set_params_format.update(|v| *v = format)
+                        }
+                    }>
+                        {params_format_options}
+                    </select>
+                    <button on:click=on_export_params_click>Export</button>
+                    <input type="file" accept=".json,.kfr,.xpf,.png" on:change=on_import_params />
+                    <span hidden=move || params_error.get().is_none()>{move || params_error.get()}</span>
+                </div>
+                <div class="gallery">
+                    <label>Gallery</label>
+                    {gallery_list}
+                </div>
+                <div class="timeline">
+                    <label>Animation</label>
+                    {keyframes}
+                    <button on:click=on_add_keyframe_click>Add keyframe</button>
+                    <div>
+                        <label>FPS</label><input type="number" value=fps on:input=move |ev| {
+                            let parsed_v = event_target_value(&ev).parse();
+                            if let Ok(parsed_v) = parsed_v {
+                                set_fps.update(|v| *v = parsed_v)
+                            }
+                        } />
+                    </div>
+                    <button on:click=on_preview_click>Preview</button>
+                    <button on:click=on_export_animation_click>Export frames</button>
+                    <canvas _ref=preview_node width=PREVIEW_WIDTH height=preview_height></canvas>
+                </div>
+                <label>{job_status}</label>
             </div>
         }
     });
 }
 
+fn load_bookmarks() -> Bookmarks {
+    leptos::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(BOOKMARKS_KEY).ok().flatten())
+        .and_then(|json| Bookmarks::from_json(&json))
+        .unwrap_or_default()
+}
+
+fn store_bookmarks(bookmarks: &Bookmarks) {
+    if let Ok(Some(storage)) = leptos::window().local_storage() {
+        let _ = storage.set_item(BOOKMARKS_KEY, &bookmarks.to_json());
+    }
+}
+
+fn load_settings() -> Settings {
+    leptos::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(SETTINGS_KEY).ok().flatten())
+        .and_then(|json| Settings::from_json(&json))
+        .unwrap_or_default()
+}
+
+fn store_settings(settings: &Settings) {
+    if let Ok(Some(storage)) = leptos::window().local_storage() {
+        let _ = storage.set_item(SETTINGS_KEY, &settings.to_json());
+    }
+}
+
+/// Draws `orbit` as a polyline over the plot of `ctx`, or clears the overlay.
+fn draw_orbit(canvas: &HtmlCanvasElement, ctx: &Ctx, layout: CanvasLayout, orbit: Option<&Orbit>) {
+    let context: CanvasRenderingContext2d =
+        canvas.get_context("2d").unwrap().unwrap().unchecked_into();
+
+    context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+
+    let Some(orbit) = orbit else {
+        return;
+    };
+
+    // Pixel centers are half a pixel into the canvas.
+    let to_canvas = |z: &Complex| {
+        (
+            (z.re as i128 - ctx.x_min) as f64 / ctx.pixel_size as f64 + 0.5,
+            (ctx.y_max - z.im as i128) as f64 / ctx.pixel_size as f64 + 0.5,
+        )
+    };
+
+    context.set_stroke_style_str("rgba(255, 255, 255, 0.8)");
+    context.set_line_width(layout.pixel_ratio);
+    context.begin_path();
+
+    for (i, z) in orbit.points.iter().enumerate() {
+        let (x, y) = to_canvas(z);
+        if i == 0 {
+            context.move_to(x, y);
+        } else {
+            context.line_to(x, y);
+        }
+    }
+
+    context.stroke();
+}
+
 fn pack_init_message(canvas: &OffscreenCanvas, ctx: &Ctx) -> JsValue {
     let msg = Array::new();
     msg.push(canvas);
@@ -213,9 +1370,92 @@ fn pack_init_message(canvas: &OffscreenCanvas, ctx: &Ctx) -> JsValue {
     msg.into()
 }
 
+fn pack_resize_message(ctx: &Ctx) -> JsValue {
+    let msg = Array::new();
+    msg.push(&"resize".into());
+    msg.push(&serde_wasm_bindgen::to_value(ctx).unwrap());
+
+    msg.into()
+}
+
 fn pack_message(ctx: &Ctx) -> JsValue {
     let msg = Array::new();
     msg.push(&serde_wasm_bindgen::to_value(ctx).unwrap());
 
     msg.into()
 }
+
+fn pack_export_message(ctx: &Ctx) -> JsValue {
+    let msg = Array::new();
+    msg.push(&"export".into());
+    msg.push(&serde_wasm_bindgen::to_value(ctx).unwrap());
+
+    msg.into()
+}
+
+fn pack_poster_message(ctx: &Ctx) -> JsValue {
+    let msg = Array::new();
+    msg.push(&"poster".into());
+    msg.push(&serde_wasm_bindgen::to_value(ctx).unwrap());
+
+    msg.into()
+}
+
+/// With `raw` set, frames are sent back one by one as RGBA data, otherwise all at once as a tar
+/// archive of PNG files.
+fn pack_frames_message(frames: &[Ctx], raw: bool) -> JsValue {
+    let msg = Array::new();
+    msg.push(&"frames".into());
+    msg.push(&serde_wasm_bindgen::to_value(frames).unwrap());
+    msg.push(&raw.into());
+
+    msg.into()
+}
+
+/// Runs the job described by `msg` on a worker of its own, so that long renders don't block
+/// the view. Replies are passed to `on_reply` until it returns true.
+fn run_job<F: FnMut(&Array) -> bool + 'static>(msg: JsValue, mut on_reply: F) {
+    let job = worker_new("worker");
+
+    let job_clone = job.clone();
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::wrap(Box::new(move |e: MessageEvent| {
+        let data = Array::from(&e.data());
+
+        // data.length == 0 means that the worker is ready
+        if data.length() == 0 {
+            let _ = job_clone.post_message(&msg);
+        } else if on_reply(&data) {
+            job_clone.terminate();
+        }
+    }));
+    job.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+}
+
+/// Lets the browser save `bytes` as a file named `file_name`.
+fn download(bytes: &JsValue, file_name: &str, mime: &str) {
+    let parts = Array::new();
+    parts.push(bytes);
+
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options).unwrap();
+    let url = Url::create_object_url_with_blob(&blob).unwrap();
+
+    let anchor: HtmlAnchorElement = leptos::document()
+        .create_element("a")
+        .unwrap()
+        .unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    // Some browsers start the download only after `click` returns.
+    set_timeout(
+        move || {
+            let _ = Url::revoke_object_url(&url);
+        },
+        REVOKE_DELAY,
+    );
+}
@@ -0,0 +1,165 @@
+use crate::{Ctx, View};
+
+/// Arrow keys move the view by this fraction of its size.
+pub const PAN_DIVISOR: i128 = 8;
+
+/// Widest span the view can be zoomed out to, in Q4.60.
+pub const MAX_SPAN: i64 = 4 << 60;
+
+/// Centers are kept within this distance from the origin, in Q4.60.
+const MAX_CENTER: i128 = 4 << 60;
+
+/// Views remembered for undoing.
+pub const MAX_HISTORY: usize = 100;
+
+/// What a key press does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyCommand {
+    /// Moves the view by a fraction of its size; `y` points up.
+    Pan {
+        x: i32,
+        y: i32,
+    },
+    ZoomIn,
+    ZoomOut,
+    Reset,
+    Undo,
+    SaveImage,
+    MoreIters,
+    FewerIters,
+    ToggleHelp,
+}
+
+/// Keys and what they do, for the help overlay.
+pub const KEY_HELP: [(&str, &str); 8] = [
+    ("Arrows", "Pan"),
+    ("+ / -", "Zoom in / out"),
+    ("[ / ]", "Fewer / more iterations"),
+    ("R", "Reset the view"),
+    ("U, Ctrl+Z", "Undo"),
+    ("S", "Save the image"),
+    ("?", "Show or hide this help"),
+    ("Esc", "Hide this help"),
+];
+
+/// Maps a `KeyboardEvent.key` to a command. Other shortcuts with Ctrl are left to the browser.
+pub fn key_command(key: &str, ctrl: bool) -> Option<KeyCommand> {
+    if ctrl {
+        return match key {
+            "z" | "Z" => Some(KeyCommand::Undo),
+            _ => None,
+        };
+    }
+
+    match key {
+        "ArrowLeft" => Some(KeyCommand::Pan { x: -1, y: 0 }),
+        "ArrowRight" => Some(KeyCommand::Pan { x: 1, y: 0 }),
+        "ArrowUp" => Some(KeyCommand::Pan { x: 0, y: 1 }),
+        "ArrowDown" => Some(KeyCommand::Pan { x: 0, y: -1 }),
+        "+" | "=" => Some(KeyCommand::ZoomIn),
+        "-" | "_" => Some(KeyCommand::ZoomOut),
+        "r" | "R" => Some(KeyCommand::Reset),
+        "u" | "U" => Some(KeyCommand::Undo),
+        "s" | "S" => Some(KeyCommand::SaveImage),
+        "]" => Some(KeyCommand::MoreIters),
+        "[" => Some(KeyCommand::FewerIters),
+        "?" | "h" | "H" => Some(KeyCommand::ToggleHelp),
+        _ => None,
+    }
+}
+
+/// Returns the view of `ctx` moved `x` steps right and `y` steps up, a step being about
+/// 1 / [`PAN_DIVISOR`] of the view's size. Steps are whole pixels, so that the view stays on
+/// the pixel grid and the points computed before can be reused.
+pub fn pan(ctx: &Ctx, x: i32, y: i32) -> View {
+    let dx = ctx.pixel_size * (ctx.win_width as i128 / PAN_DIVISOR) * x as i128;
+    let dy = ctx.pixel_size * (ctx.win_height as i128 / PAN_DIVISOR) * y as i128;
+
+    View {
+        center_x: (ctx.center_x + dx).clamp(-MAX_CENTER, MAX_CENTER) as i64,
+        center_y: (ctx.center_y + dy).clamp(-MAX_CENTER, MAX_CENTER) as i64,
+        ..ctx.view()
+    }
+}
+
+/// Returns the view of `ctx` zoomed in or out by a factor of two around its center.
+///
+/// The pixel size is doubled or, when it is even, halved exactly rather than the span, so that
+/// the new pixel grid is a subset or a refinement of the old one and the points of the old plot
+/// can be reused.
+pub fn zoom(ctx: &Ctx, zoom_in: bool) -> View {
+    let width = ctx.win_width as i128;
+    let pixel_size = if zoom_in {
+        (ctx.pixel_size / 2).max(1)
+    } else {
+        (ctx.pixel_size * 2).min(MAX_SPAN as i128 / width)
+    };
+
+    View {
+        horizontal_span: (pixel_size * width) as i64,
+        ..ctx.view()
+    }
+}
+
+/// Returns the view `r` goes back to, with the default limit.
+pub fn default_view(ctx: &Ctx) -> View {
+    if ctx.julia.is_some() {
+        View::julia()
+    } else {
+        View::default()
+    }
+}
+
+/// Doubles or halves the limit, keeping it at least 1.
+pub fn change_max_iters(max_iters: usize, more: bool) -> usize {
+    if more {
+        max_iters.saturating_mul(2)
+    } else {
+        (max_iters / 2).max(1)
+    }
+}
+
+/// Returns `ctx` with its limit doubled or halved by [`change_max_iters`], or `None` if the
+/// limit is chosen automatically.
+pub fn step_max_iters(ctx: &Ctx, more: bool) -> Option<Ctx> {
+    if ctx.auto_iters {
+        return None;
+    }
+
+    Some(Ctx {
+        max_iters: change_max_iters(ctx.max_iters, more),
+        needs_recalc: true,
+        ..*ctx
+    })
+}
+
+/// Previously shown views, most recent last.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    views: Vec<View>,
+}
+
+impl History {
+    /// Remembers `view`, forgetting the oldest one when full. Repeated views are kept once.
+    pub fn push(&mut self, view: View) {
+        if self.views.last() == Some(&view) {
+            return;
+        }
+        if self.views.len() == MAX_HISTORY {
+            self.views.remove(0);
+        }
+        self.views.push(view);
+    }
+
+    pub fn pop(&mut self) -> Option<View> {
+        self.views.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+use crate::precision::{Arbitrary, Backend, Fixed, Float};
+use crate::{Complex, Ctx, Precision};
+
+/// Most orbit points kept for drawing; the iteration goes on without them.
+pub const MAX_ORBIT_POINTS: usize = 4096;
+
+/// Longest period looked for.
+pub const MAX_PERIOD: usize = 1024;
+
+/// Points closer than this, in Q4.60 units, count as the same when looking for a period.
+const PERIOD_TOLERANCE: i128 = 1 << 30;
+
+/// The sequence `z_n` of one point.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Orbit {
+    /// The first values of `z_n`, starting with `z_0`.
+    pub points: Vec<Complex>,
+    /// Number of iterations done.
+    pub iterations: usize,
+    /// Iteration at which the orbit escaped, if it did.
+    pub escape: Option<usize>,
+    /// Length of the cycle the orbit settled in, if it did not escape.
+    pub period: Option<usize>,
+}
+
+/// Computes the orbit of the pixel at `x`, `y` with the formula and limit of `ctx`.
+pub fn orbit_at(ctx: &Ctx, x: usize, y: usize) -> Orbit {
+    let (re, im) = ctx.get_coords(x, y);
+    let point = Complex::new(re, im);
+
+    orbit(
+        point,
+        ctx.julia.unwrap_or(point),
+        ctx.max_iters,
+        ctx.threshold(),
+        ctx.backend(),
+    )
+}
+
+/// Iterates `z = z^2 + c` from `z0` in the representation `precision`, like the renderer
+/// does, recording the values until they pass the squared bailout radius `threshold`.
+pub fn orbit(
+    z0: Complex,
+    c: Complex,
+    max_iters: usize,
+    threshold: i128,
+    precision: Precision,
+) -> Orbit {
+    match precision {
+        Precision::Float => orbit_in::<Float>(z0, c, max_iters, threshold),
+        Precision::Auto | Precision::Fixed => orbit_in::<Fixed>(z0, c, max_iters, threshold),
+        Precision::Arbitrary => orbit_in::<Arbitrary>(z0, c, max_iters, threshold),
+    }
+}
+
+fn orbit_in<B: Backend>(z0: Complex, c: Complex, max_iters: usize, threshold: i128) -> Orbit {
+    let mut orbit = Orbit::default();
+    let mut recent = VecDeque::with_capacity(MAX_PERIOD + 1);
+    let threshold = B::threshold(threshold);
+    let c = B::from_fixed(c);
+    let mut value = B::from_fixed(z0);
+
+    for i in 0..=max_iters {
+        let z = B::to_fixed(&value);
+        if orbit.points.len() < MAX_ORBIT_POINTS {
+            orbit.points.push(z);
+        }
+        if recent.len() > MAX_PERIOD {
+            recent.pop_front();
+        }
+        recent.push_back(z);
+        orbit.iterations = i;
+
+        match B::step(&value, &c, &threshold) {
+            Ok(next) => value = next,
+            Err(_) => {
+                orbit.escape = Some(i);
+                return orbit;
+            }
+        }
+    }
+
+    orbit.period = find_period(&recent);
+    orbit
+}
+
+/// Returns the smallest distance back from the last point to one close to it.
+fn find_period(recent: &VecDeque<Complex>) -> Option<usize> {
+    let last = recent.back()?;
+
+    (1..recent.len()).find(|&p| {
+        let other = recent[recent.len() - 1 - p];
+        let re = last.re as i128 - other.re as i128;
+        let im = last.im as i128 - other.im as i128;
+
+        re * re + im * im < PERIOD_TOLERANCE * PERIOD_TOLERANCE
+    })
+}
@@ -0,0 +1,59 @@
+use rayon::prelude::*;
+
+use crate::{mirror_rows, process_plot_cpu, Ctx, PlotPoint};
+
+/// Processes every remaining chunk of rows of `plot` at once, one chunk per task.
+///
+/// The chunks are the ones successive calls of [`process_plot_cpu`] with the same `ctx` would
+/// process, starting at `ctx.rows_processed`. Each of them is processed by the same code on its
+/// own copy of `ctx`, so the result matches the single-threaded one pixel for pixel, whatever
+/// the strategy. Returns the number of rows processed.
+pub fn process_plot_parallel(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) -> usize {
+    let start = ctx.rows_processed.min(plot.len());
+    let chunk_size = ctx.chunk_size.max(1);
+    let first = *ctx;
+
+    let ranges: Vec<(i128, i128)> = plot[start..]
+        .par_chunks_mut(chunk_size)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut chunk_ctx = Ctx {
+                rows_processed: 0,
+                offset: first.offset + start + i * chunk_size,
+                ..first
+            };
+            process_plot_cpu(&mut chunk_ctx, chunk);
+
+            (chunk_ctx.min_value, chunk_ctx.max_value)
+        })
+        .collect();
+
+    for (min_value, max_value) in ranges {
+        ctx.min_value = ctx.min_value.min(min_value);
+        ctx.max_value = ctx.max_value.max(max_value);
+    }
+
+    plot.len() - start
+}
+
+/// Computes every point of the view described by `ctx` like [`crate::render_plot`], but in
+/// chunks of `ctx.chunk_size` rows processed in parallel.
+///
+/// The plot is the same as the one of a chunk by chunk render with
+/// [`crate::process_plot_symmetric`], as in the worker. Brute force renders don't depend on
+/// the chunks, so for them it is also the same as that of [`crate::render_plot`].
+pub fn render_plot_parallel(ctx: &mut Ctx) -> Vec<Vec<PlotPoint>> {
+    let mut plot = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+
+    ctx.reset_min_max();
+    ctx.offset = 0;
+    ctx.rows_processed = 0;
+    ctx.step = 1;
+
+    let rows = ctx.computed_rows();
+    ctx.offset = rows.start;
+    process_plot_parallel(ctx, &mut plot[rows.clone()]);
+    mirror_rows(ctx, &mut plot, &rows, &(0..rows.len()));
+
+    plot
+}
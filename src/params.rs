@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixed::{from_decimal, to_decimal, FRACTION_BITS};
+use crate::{Complex, Ctx, View};
+
+/// Number of colors Kalles Fraktaler's palette offset counts in.
+const KFR_COLORS: f64 = 1024.0;
+
+/// Number of colors XaoS shifts its palette by.
+const XPF_COLORS: f64 = 256.0;
+
+/// File formats the view and palette settings can be exchanged in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamsFormat {
+    /// Kalles Fraktaler's `.kfr` files.
+    Kfr,
+    /// XaoS position files, `.xpf`.
+    Xpf,
+    /// The native format, with exact coordinates.
+    Json,
+}
+
+impl ParamsFormat {
+    pub const ALL: [ParamsFormat; 3] = [ParamsFormat::Json, ParamsFormat::Kfr, ParamsFormat::Xpf];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ParamsFormat::Kfr => "kfr",
+            ParamsFormat::Xpf => "xpf",
+            ParamsFormat::Json => "json",
+        }
+    }
+
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let (_, extension) = name.rsplit_once('.')?;
+
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+    }
+
+    pub fn export(&self, ctx: &Ctx) -> String {
+        match self {
+            ParamsFormat::Kfr => to_kfr(ctx),
+            ParamsFormat::Xpf => to_xpf(ctx),
+            ParamsFormat::Json => to_json(ctx),
+        }
+    }
+
+    /// Applies the parameters in `text` to `ctx`. Settings missing from the file are left as
+    /// they are. `ctx` is only changed if the result passes [`Ctx::validate`].
+    pub fn import(&self, text: &str, ctx: &mut Ctx) -> Result<(), String> {
+        let mut imported = *ctx;
+        match self {
+            ParamsFormat::Kfr => from_kfr(text, &mut imported),
+            ParamsFormat::Xpf => from_xpf(text, &mut imported),
+            ParamsFormat::Json => from_json(text, &mut imported),
+        }?;
+        imported.validate()?;
+
+        *ctx = imported;
+
+        Ok(())
+    }
+}
+
+/// The native format. Coordinates are decimals, so that they are exact and readable.
+#[derive(Serialize, Deserialize)]
+struct JsonParams {
+    center_x: String,
+    center_y: String,
+    span: String,
+    max_iters: usize,
+    palette_offset: f64,
+    brightness: f64,
+    julia: Option<JsonComplex>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonComplex {
+    re: String,
+    im: String,
+}
+
+fn to_json(ctx: &Ctx) -> String {
+    let view = ctx.view();
+    let params = JsonParams {
+        center_x: to_decimal(view.center_x),
+        center_y: to_decimal(view.center_y),
+        span: to_decimal(view.horizontal_span),
+        max_iters: view.max_iters,
+        palette_offset: ctx.palette_offset,
+        brightness: ctx.brightness,
+        julia: ctx.julia.map(|c| JsonComplex {
+            re: to_decimal(c.re),
+            im: to_decimal(c.im),
+        }),
+    };
+
+    serde_json::to_string_pretty(&params).expect("Parameters should always serialize.")
+}
+
+fn from_json(text: &str, ctx: &mut Ctx) -> Result<(), String> {
+    let params: JsonParams = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+    let view = View {
+        center_x: coordinate(&params.center_x)?,
+        center_y: coordinate(&params.center_y)?,
+        horizontal_span: span(coordinate(&params.span)?)?,
+        max_iters: params.max_iters,
+    };
+    let julia = match params.julia {
+        Some(c) => Some(Complex::new(coordinate(&c.re)?, coordinate(&c.im)?)),
+        None => None,
+    };
+
+    ctx.set_view(&view);
+    ctx.palette_offset = params.palette_offset;
+    ctx.brightness = params.brightness;
+    ctx.julia = julia;
+
+    Ok(())
+}
+
+/// Kalles Fraktaler describes the view by the magnification of a circle of radius 2 that fits
+/// the height of the image.
+fn to_kfr(ctx: &Ctx) -> String {
+    let view = ctx.view();
+    let zoom = 4.0 / to_f64(ctx.vertical_span);
+    let color_offset = (ctx.palette_offset.rem_euclid(1.0) * KFR_COLORS).round() as usize;
+
+    format!(
+        "Re: {}\r\nIm: {}\r\nZoom: {zoom:E}\r\nIterations: {}\r\nColorOffset: {color_offset}\r\n",
+        to_decimal(view.center_x),
+        to_decimal(view.center_y),
+        view.max_iters,
+    )
+}
+
+fn from_kfr(text: &str, ctx: &mut Ctx) -> Result<(), String> {
+    let values: HashMap<&str, &str> = text
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let value = |key: &str| {
+        values
+            .get(key)
+            .copied()
+            .ok_or_else(|| format!("Missing {key}."))
+    };
+
+    let zoom: f64 = value("Zoom")?
+        .parse()
+        .map_err(|_| "Invalid Zoom.".to_string())?;
+    let vertical_span = 4.0 / zoom;
+    let horizontal_span = vertical_span * ctx.win_width as f64 / ctx.win_height as f64;
+
+    let view = View {
+        center_x: coordinate(value("Re")?)?,
+        center_y: coordinate(value("Im")?)?,
+        horizontal_span: span(from_f64(horizontal_span)?)?,
+        max_iters: match value("Iterations") {
+            Ok(iters) => iters.parse().map_err(|_| "Invalid Iterations.")?,
+            Err(_) => ctx.max_iters,
+        },
+    };
+
+    ctx.set_view(&view);
+    ctx.julia = None;
+    if let Ok(offset) = value("ColorOffset") {
+        let offset: f64 = offset.parse().map_err(|_| "Invalid ColorOffset.")?;
+        ctx.palette_offset = offset / KFR_COLORS;
+    }
+
+    Ok(())
+}
+
+/// XaoS position files are scripts of s-expressions; only the commands describing the view
+/// are written and read.
+fn to_xpf(ctx: &Ctx) -> String {
+    let view = ctx.view();
+    let shift = (ctx.palette_offset.rem_euclid(1.0) * XPF_COLORS).round() as usize;
+
+    let mut s = String::from(";Position file of the Mandelbrot set viewer\n(initstate)\n");
+    s.push_str("(formula 'mandel)\n");
+    if let Some(c) = ctx.julia {
+        s.push_str(&format!(
+            "(julia #t)\n(juliaseed {} {})\n",
+            to_decimal(c.re),
+            to_decimal(c.im)
+        ));
+    }
+    s.push_str(&format!(
+        "(view {} {} {} {})\n",
+        to_decimal(view.center_x),
+        to_decimal(view.center_y),
+        to_decimal(view.horizontal_span),
+        to_decimal(ctx.vertical_span as i64),
+    ));
+    s.push_str(&format!("(maxiter {})\n", view.max_iters));
+    s.push_str(&format!("(shiftpalette {shift})\n"));
+
+    s
+}
+
+fn from_xpf(text: &str, ctx: &mut Ctx) -> Result<(), String> {
+    let mut view = None;
+    let mut max_iters = ctx.max_iters;
+    let mut julia = false;
+    let mut seed = None;
+    let mut shift = None;
+
+    for command in text
+        .lines()
+        .map(|line| line.split(';').next().unwrap_or_default())
+        .flat_map(|line| line.split('('))
+        .map(|command| command.trim().trim_end_matches(')').trim())
+        .filter(|command| !command.is_empty())
+    {
+        let mut args = command.split_whitespace();
+        let name = args.next().unwrap_or_default();
+        let args: Vec<&str> = args.collect();
+        let arg = |i: usize| {
+            args.get(i)
+                .copied()
+                .ok_or_else(|| format!("Missing argument of {name}."))
+        };
+
+        match name {
+            "view" => view = Some((coordinate(arg(0)?)?, coordinate(arg(1)?)?, arg(2)?)),
+            "maxiter" => max_iters = arg(0)?.parse().map_err(|_| "Invalid maxiter.")?,
+            "julia" => julia = arg(0)? == "#t",
+            "juliaseed" => seed = Some(Complex::new(coordinate(arg(0)?)?, coordinate(arg(1)?)?)),
+            "shiftpalette" => shift = Some(arg(0)?.parse::<f64>().map_err(|_| "Invalid shift.")?),
+            _ => {}
+        }
+    }
+
+    let (center_x, center_y, width) = view.ok_or("Missing view.")?;
+
+    ctx.set_view(&View {
+        center_x,
+        center_y,
+        horizontal_span: span(coordinate(width)?)?,
+        max_iters,
+    });
+    ctx.julia = if julia {
+        Some(seed.ok_or("Missing juliaseed.")?)
+    } else {
+        None
+    };
+    if let Some(shift) = shift {
+        ctx.palette_offset = shift / XPF_COLORS;
+    }
+
+    Ok(())
+}
+
+/// Parses a decimal coordinate exactly, or one in scientific notation as an `f64`.
+fn coordinate(s: &str) -> Result<i64, String> {
+    if s.contains(['e', 'E']) {
+        let v: f64 = s.parse().map_err(|_| format!("Invalid number {s}."))?;
+        return from_f64(v);
+    }
+
+    from_decimal(s).ok_or_else(|| format!("Invalid coordinate {s}."))
+}
+
+fn span(span: i64) -> Result<i64, String> {
+    if span > 0 {
+        Ok(span)
+    } else {
+        Err("The span must be positive.".to_string())
+    }
+}
+
+fn to_f64(v: i128) -> f64 {
+    v as f64 / (1_u64 << FRACTION_BITS) as f64
+}
+
+fn from_f64(v: f64) -> Result<i64, String> {
+    let fixed = (v * (1_u64 << FRACTION_BITS) as f64).round();
+
+    if fixed.is_finite() && fixed.abs() < i64::MAX as f64 {
+        Ok(fixed as i64)
+    } else {
+        Err(format!("{v} is out of range."))
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::Write;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::color::colorize;
+use crate::export::png_encoder;
+use crate::{render_plot, Ctx};
+
+/// Width and height of the tiles a poster is computed in.
+pub const TILE_SIZE: usize = 256;
+
+/// Largest dimension of the preview used to find the color range of a poster.
+const PREVIEW_SIZE: usize = 512;
+
+/// Renders the view of `ctx` tile by tile and streams it as PNG into `w`.
+///
+/// Only one row of tiles is kept in memory at a time, so the size of the image is bounded
+/// by the output format only. With the `parallel` feature, the tiles of a row are rendered
+/// in parallel. `progress` is called with the number of rows written so far.
+pub fn render_poster<W: Write, P: FnMut(usize)>(
+    ctx: &Ctx,
+    grad: &colorgrad::Gradient,
+    w: W,
+    mut progress: P,
+) -> Result<(), png::EncodingError> {
+    let mut ctx = *ctx;
+    estimate_min_max(&mut ctx);
+
+    let mut writer = png_encoder(&ctx, w)?.write_header()?;
+    let mut stream = writer.stream_writer()?;
+
+    let row_len = 4 * ctx.win_width;
+    let mut band = vec![0_u8; row_len * TILE_SIZE];
+
+    for y in (0..ctx.win_height).step_by(TILE_SIZE) {
+        let h = TILE_SIZE.min(ctx.win_height - y);
+
+        let render_tile = |x: usize| {
+            let w = TILE_SIZE.min(ctx.win_width - x);
+
+            let mut tile_ctx = ctx.sub_ctx(x, y, w, h);
+            let plot = render_plot(&mut tile_ctx);
+            tile_ctx.min_value = ctx.min_value;
+            tile_ctx.max_value = ctx.max_value;
+
+            let mut tile = vec![0_u8; 4 * w * h];
+            colorize(&tile_ctx, grad, &plot, &mut tile);
+
+            (x, w, tile)
+        };
+
+        let xs: Vec<usize> = (0..ctx.win_width).step_by(TILE_SIZE).collect();
+        // Tiles are rendered independently of each other, so the order doesn't matter.
+        #[cfg(feature = "parallel")]
+        let tiles: Vec<_> = xs.into_par_iter().map(render_tile).collect();
+        #[cfg(not(feature = "parallel"))]
+        let tiles: Vec<_> = xs.into_iter().map(render_tile).collect();
+
+        for (x, w, tile) in tiles {
+            for (tile_y, tile_row) in tile.chunks_exact(4 * w).enumerate() {
+                let start = tile_y * row_len + 4 * x;
+                band[start..start + 4 * w].copy_from_slice(tile_row);
+            }
+        }
+
+        stream.write_all(&band[..row_len * h])?;
+        progress(y + h);
+    }
+
+    stream.finish()?;
+
+    Ok(())
+}
+
+/// Sets the color range of `ctx` from a low resolution render of the same view.
+fn estimate_min_max(ctx: &mut Ctx) {
+    let scale = ctx
+        .win_width
+        .max(ctx.win_height)
+        .div_ceil(PREVIEW_SIZE)
+        .max(1);
+
+    let mut preview_ctx = ctx
+        .resized(
+            (ctx.win_width / scale).max(1),
+            (ctx.win_height / scale).max(1),
+        )
+        .expect("the preview is at least one pixel in each direction");
+    render_plot(&mut preview_ctx);
+
+    ctx.min_value = preview_ctx.min_value;
+    ctx.max_value = preview_ctx.max_value;
+}
@@ -0,0 +1,210 @@
+use num_bigint::{BigInt, Sign};
+
+use crate::{step, Complex, PlotPoint};
+
+/// One in Q4.60.
+const ONE: f64 = (1_u64 << 60) as f64;
+
+/// Converts a Q4.60 number to the nearest `f64`.
+pub(crate) fn to_float(v: i64) -> f64 {
+    v as f64 / ONE
+}
+
+/// Converts an `f64` to the nearest Q4.60 number, saturating outside of the range.
+pub(crate) fn from_float(v: f64) -> i64 {
+    (v * ONE).round() as i64
+}
+
+/// Converts a squared magnitude computed in `f64` to Q4.60.
+pub(crate) fn norm_from_float(n: f64) -> i128 {
+    (n * ONE) as i128
+}
+
+/// Smallest pixel spacing, in Q4.60 units, that `f64` is accurate for. Its 52 fractional bits
+/// leave about ten bits of headroom at 2^-42.
+pub const MIN_FLOAT_PIXEL_SIZE: i128 = 1 << 18;
+
+/// Smallest pixel spacing, in Q4.60 units, that Q4.60 is accurate for, with the same headroom.
+pub const MIN_FIXED_PIXEL_SIZE: i128 = 1 << 10;
+
+/// Fractional bits of the arbitrary precision numbers, twice those of the coordinates so that
+/// rounding errors stay far below the pixel spacing. Points are still Q4.60, so views can't
+/// be deeper than a pixel spacing of 2^-60 with any backend.
+pub const ARBITRARY_FRACTION_BITS: u32 = 120;
+
+/// A number representation the escape-time iteration can run in.
+///
+/// Points and the values stored for resuming are Q4.60 in every backend; only the
+/// iteration itself runs in the backend's representation.
+pub(crate) trait Backend {
+    type Value;
+    type Threshold;
+
+    fn from_fixed(z: Complex) -> Self::Value;
+    fn to_fixed(z: &Self::Value) -> Complex;
+    fn threshold(threshold: i128) -> Self::Threshold;
+
+    /// Returns `z^2 + c`, or the squared magnitude of `z` in Q4.60 if it is beyond
+    /// `threshold`.
+    fn step(
+        z: &Self::Value,
+        c: &Self::Value,
+        threshold: &Self::Threshold,
+    ) -> Result<Self::Value, i128>;
+}
+
+/// Continues iterating from `z`, the value after `first` iterations, until it escapes or
+/// `max_iters` is reached.
+pub(crate) fn iterate<B: Backend>(
+    z: Complex,
+    c: Complex,
+    first: usize,
+    max_iters: usize,
+    threshold: i128,
+    p: &mut PlotPoint,
+) {
+    let threshold = B::threshold(threshold);
+    let c = B::from_fixed(c);
+    let mut z = B::from_fixed(z);
+
+    for i in first..=max_iters {
+        match B::step(&z, &c, &threshold) {
+            Ok(next) => z = next,
+            Err(n) => {
+                p.calculate(n, i);
+                return;
+            }
+        }
+    }
+
+    p.stop(B::to_fixed(&z), max_iters);
+}
+
+pub(crate) struct Float;
+
+#[derive(Clone, Copy)]
+pub(crate) struct FloatComplex {
+    re: f64,
+    im: f64,
+}
+
+impl Backend for Float {
+    type Value = FloatComplex;
+    type Threshold = f64;
+
+    fn from_fixed(z: Complex) -> FloatComplex {
+        FloatComplex {
+            re: to_float(z.re),
+            im: to_float(z.im),
+        }
+    }
+
+    fn to_fixed(z: &FloatComplex) -> Complex {
+        Complex::new(from_float(z.re), from_float(z.im))
+    }
+
+    fn threshold(threshold: i128) -> f64 {
+        threshold as f64 / ONE
+    }
+
+    #[inline(always)]
+    fn step(z: &FloatComplex, c: &FloatComplex, threshold: &f64) -> Result<FloatComplex, i128> {
+        let re_sq = z.re * z.re;
+        let im_sq = z.im * z.im;
+
+        let n = re_sq + im_sq;
+        if n > *threshold {
+            return Err(norm_from_float(n));
+        }
+
+        Ok(FloatComplex {
+            re: re_sq - im_sq + c.re,
+            im: 2.0 * z.re * z.im + c.im,
+        })
+    }
+}
+
+pub(crate) struct Fixed;
+
+impl Backend for Fixed {
+    type Value = Complex;
+    type Threshold = i128;
+
+    fn from_fixed(z: Complex) -> Complex {
+        z
+    }
+
+    fn to_fixed(z: &Complex) -> Complex {
+        *z
+    }
+
+    fn threshold(threshold: i128) -> i128 {
+        threshold
+    }
+
+    #[inline(always)]
+    fn step(z: &Complex, c: &Complex, threshold: &i128) -> Result<Complex, i128> {
+        step(*z, *c, *threshold)
+    }
+}
+
+pub(crate) struct Arbitrary;
+
+pub(crate) struct BigComplex {
+    re: BigInt,
+    im: BigInt,
+}
+
+/// Bits the arbitrary precision numbers have below those of Q4.60.
+const EXTRA_BITS: u32 = ARBITRARY_FRACTION_BITS - 60;
+
+impl Backend for Arbitrary {
+    type Value = BigComplex;
+    type Threshold = BigInt;
+
+    fn from_fixed(z: Complex) -> BigComplex {
+        BigComplex {
+            re: BigInt::from(z.re) << EXTRA_BITS,
+            im: BigInt::from(z.im) << EXTRA_BITS,
+        }
+    }
+
+    fn to_fixed(z: &BigComplex) -> Complex {
+        let round = |v: &BigInt| {
+            let v: BigInt = (v + (BigInt::from(1) << (EXTRA_BITS - 1))) >> EXTRA_BITS;
+            i64::try_from(&v).unwrap_or(if v.sign() == Sign::Minus {
+                i64::MIN
+            } else {
+                i64::MAX
+            })
+        };
+
+        Complex::new(round(&z.re), round(&z.im))
+    }
+
+    fn threshold(threshold: i128) -> BigInt {
+        BigInt::from(threshold) << EXTRA_BITS
+    }
+
+    fn step(z: &BigComplex, c: &BigComplex, threshold: &BigInt) -> Result<BigComplex, i128> {
+        let re_sq: BigInt = (&z.re * &z.re) >> ARBITRARY_FRACTION_BITS;
+        let im_sq: BigInt = (&z.im * &z.im) >> ARBITRARY_FRACTION_BITS;
+
+        let n = &re_sq + &im_sq;
+        if n > *threshold {
+            return Err(i128::try_from(n >> EXTRA_BITS).unwrap_or(i128::MAX));
+        }
+        // Rounded towards zero like in Q4.60, so that conjugate points stay conjugate.
+        let re_im = &z.re * &z.im;
+        let re_im = if re_im.sign() == Sign::Minus {
+            -(-re_im >> (ARBITRARY_FRACTION_BITS - 1))
+        } else {
+            re_im >> (ARBITRARY_FRACTION_BITS - 1)
+        };
+
+        Ok(BigComplex {
+            re: re_sq - im_sq + &c.re,
+            im: re_im + &c.im,
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Complex, Ctx, Precision, RenderStrategy, View};
+
+/// Version of [`Settings`]. Saved settings of another version are ignored, so it has to be
+/// bumped whenever their meaning changes.
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// The user's choices that outlive a session.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub version: u32,
+    /// The last view, with its iteration limit.
+    pub view: View,
+    pub julia: Option<Complex>,
+    pub auto_iters: bool,
+    pub brightness: f64,
+    pub palette_offset: f64,
+    pub chunk_size: usize,
+    pub strategy: RenderStrategy,
+    pub progressive: bool,
+    pub symmetry: bool,
+    pub bailout: f64,
+    pub precision: Precision,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let ctx = Ctx::default();
+
+        Self {
+            version: SETTINGS_VERSION,
+            view: View::default(),
+            julia: ctx.julia,
+            auto_iters: ctx.auto_iters,
+            brightness: ctx.brightness,
+            palette_offset: ctx.palette_offset,
+            chunk_size: ctx.chunk_size,
+            strategy: ctx.strategy,
+            progressive: ctx.progressive,
+            symmetry: ctx.symmetry,
+            bailout: ctx.bailout,
+            precision: ctx.precision,
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_ctx(ctx: &Ctx) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            view: ctx.view(),
+            julia: ctx.julia,
+            auto_iters: ctx.auto_iters,
+            brightness: ctx.brightness,
+            palette_offset: ctx.palette_offset,
+            chunk_size: ctx.chunk_size,
+            strategy: ctx.strategy,
+            progressive: ctx.progressive,
+            symmetry: ctx.symmetry,
+            bailout: ctx.bailout,
+            precision: ctx.precision,
+        }
+    }
+
+    pub fn apply_to(&self, ctx: &mut Ctx) {
+        ctx.julia = self.julia;
+        ctx.auto_iters = self.auto_iters;
+        ctx.brightness = self.brightness;
+        ctx.palette_offset = self.palette_offset;
+        ctx.chunk_size = self.chunk_size;
+        ctx.strategy = self.strategy;
+        ctx.progressive = self.progressive;
+        ctx.symmetry = self.symmetry;
+        ctx.bailout = self.bailout;
+        ctx.precision = self.precision;
+        ctx.set_view(&self.view);
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Settings should always serialize.")
+    }
+
+    /// Parses saved settings, returning `None` for ones of another version or that can't be
+    /// rendered.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let settings: Self = serde_json::from_str(json).ok()?;
+
+        let mut ctx = Ctx {
+            win_width: 1,
+            win_height: 1,
+            ..Default::default()
+        };
+        settings.apply_to(&mut ctx);
+
+        let valid = settings.version == SETTINGS_VERSION
+            && ctx.validate().is_ok()
+            && settings.brightness.is_finite()
+            && settings.palette_offset.is_finite();
+
+        valid.then_some(settings)
+    }
+}
@@ -0,0 +1,269 @@
+use crate::precision::{from_float, norm_from_float, to_float, Backend, Float};
+use crate::{Complex, Ctx, PlotPoint};
+
+/// Number of points iterated at once.
+pub const LANES: usize = 2;
+
+/// Vector of [`LANES`] `f64`s. Implementations must round exactly like scalar `f64`
+/// arithmetic, so that the kernel matches [`Float`] bit for bit.
+trait Lanes: Copy {
+    fn splat(v: f64) -> Self;
+    fn from_array(a: [f64; LANES]) -> Self;
+    fn to_array(self) -> [f64; LANES];
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    /// Returns a mask with bit `i` set if lane `i` is greater than that of `other`.
+    fn gt_mask(self, other: Self) -> u32;
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod arch {
+    use super::{Lanes, LANES};
+    use core::arch::wasm32::*;
+
+    pub type Native = v128;
+
+    impl Lanes for v128 {
+        #[inline(always)]
+        fn splat(v: f64) -> Self {
+            f64x2_splat(v)
+        }
+
+        #[inline(always)]
+        fn from_array(a: [f64; LANES]) -> Self {
+            f64x2(a[0], a[1])
+        }
+
+        #[inline(always)]
+        fn to_array(self) -> [f64; LANES] {
+            [f64x2_extract_lane::<0>(self), f64x2_extract_lane::<1>(self)]
+        }
+
+        #[inline(always)]
+        fn add(self, other: Self) -> Self {
+            f64x2_add(self, other)
+        }
+
+        #[inline(always)]
+        fn sub(self, other: Self) -> Self {
+            f64x2_sub(self, other)
+        }
+
+        #[inline(always)]
+        fn mul(self, other: Self) -> Self {
+            f64x2_mul(self, other)
+        }
+
+        #[inline(always)]
+        fn gt_mask(self, other: Self) -> u32 {
+            i64x2_bitmask(f64x2_gt(self, other)) as u32
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod arch {
+    use super::{Lanes, LANES};
+    use std::arch::x86_64::*;
+
+    pub type Native = __m128d;
+
+    // The SSE2 intrinsics are unsafe to call, but SSE2 is part of the x86_64 baseline.
+    impl Lanes for __m128d {
+        #[inline(always)]
+        fn splat(v: f64) -> Self {
+            unsafe { _mm_set1_pd(v) }
+        }
+
+        #[inline(always)]
+        fn from_array(a: [f64; LANES]) -> Self {
+            unsafe { _mm_set_pd(a[1], a[0]) }
+        }
+
+        #[inline(always)]
+        fn to_array(self) -> [f64; LANES] {
+            unsafe {
+                [
+                    _mm_cvtsd_f64(self),
+                    _mm_cvtsd_f64(_mm_unpackhi_pd(self, self)),
+                ]
+            }
+        }
+
+        #[inline(always)]
+        fn add(self, other: Self) -> Self {
+            unsafe { _mm_add_pd(self, other) }
+        }
+
+        #[inline(always)]
+        fn sub(self, other: Self) -> Self {
+            unsafe { _mm_sub_pd(self, other) }
+        }
+
+        #[inline(always)]
+        fn mul(self, other: Self) -> Self {
+            unsafe { _mm_mul_pd(self, other) }
+        }
+
+        #[inline(always)]
+        fn gt_mask(self, other: Self) -> u32 {
+            unsafe { _mm_movemask_pd(_mm_cmpgt_pd(self, other)) as u32 }
+        }
+    }
+}
+
+/// Scalar fallback for targets without a SIMD implementation.
+#[cfg(not(any(
+    all(target_arch = "wasm32", target_feature = "simd128"),
+    target_arch = "x86_64"
+)))]
+mod arch {
+    use super::{Lanes, LANES};
+
+    pub type Native = [f64; LANES];
+
+    impl Lanes for [f64; LANES] {
+        fn splat(v: f64) -> Self {
+            [v; LANES]
+        }
+
+        fn from_array(a: [f64; LANES]) -> Self {
+            a
+        }
+
+        fn to_array(self) -> [f64; LANES] {
+            self
+        }
+
+        fn add(self, other: Self) -> Self {
+            std::array::from_fn(|i| self[i] + other[i])
+        }
+
+        fn sub(self, other: Self) -> Self {
+            std::array::from_fn(|i| self[i] - other[i])
+        }
+
+        fn mul(self, other: Self) -> Self {
+            std::array::from_fn(|i| self[i] * other[i])
+        }
+
+        fn gt_mask(self, other: Self) -> u32 {
+            (0..LANES).fold(0, |mask, i| mask | ((self[i] > other[i]) as u32) << i)
+        }
+    }
+}
+
+/// Iterates `z = z^2 + c` for [`LANES`] points at once in `f64`, with the same results as
+/// [`Float`]. Lanes that have escaped keep being computed, but their results are ignored.
+fn iterate<V: Lanes>(
+    z0: [Complex; LANES],
+    c: [Complex; LANES],
+    max_iters: usize,
+    threshold: i128,
+    points: &mut [PlotPoint; LANES],
+) {
+    let threshold = V::splat(Float::threshold(threshold));
+    let two = V::splat(2.0);
+    let c_re = V::from_array(c.map(|c| to_float(c.re)));
+    let c_im = V::from_array(c.map(|c| to_float(c.im)));
+    let mut re = V::from_array(z0.map(|z| to_float(z.re)));
+    let mut im = V::from_array(z0.map(|z| to_float(z.im)));
+
+    let mut active = (1 << LANES) - 1;
+
+    for i in 0..=max_iters {
+        let re_sq = re.mul(re);
+        let im_sq = im.mul(im);
+
+        let n = re_sq.add(im_sq);
+        let escaped = n.gt_mask(threshold) & active;
+        if escaped != 0 {
+            let n = n.to_array();
+            for lane in (0..LANES).filter(|lane| escaped & 1 << lane != 0) {
+                points[lane].calculate(norm_from_float(n[lane]), i);
+            }
+
+            active &= !escaped;
+            if active == 0 {
+                return;
+            }
+        }
+
+        // The same operations in the same order as `Float::step`.
+        im = two.mul(re).mul(im).add(c_im);
+        re = re_sq.sub(im_sq).add(c_re);
+    }
+
+    let (re, im) = (re.to_array(), im.to_array());
+    for lane in (0..LANES).filter(|lane| active & 1 << lane != 0) {
+        points[lane].stop(
+            Complex::new(from_float(re[lane]), from_float(im[lane])),
+            max_iters,
+        );
+    }
+}
+
+/// Computes `points`, which lie at `coords`, from scratch. The last batch is padded by
+/// repeating its last point.
+fn compute_batches(ctx: &Ctx, coords: &[(i64, i64)], points: &mut [PlotPoint]) {
+    let threshold = ctx.threshold();
+
+    for (coords, points) in coords.chunks(LANES).zip(points.chunks_mut(LANES)) {
+        let lane = |i: usize| {
+            let (re, im) = coords[i.min(coords.len() - 1)];
+            Complex::new(re, im)
+        };
+        let z0: [Complex; LANES] = std::array::from_fn(lane);
+        let c = z0.map(|z| ctx.julia.unwrap_or(z));
+
+        let mut batch = [PlotPoint::default(); LANES];
+        iterate::<arch::Native>(z0, c, ctx.max_iters, threshold, &mut batch);
+
+        points.copy_from_slice(&batch[..points.len()]);
+    }
+}
+
+/// Computes every point of row `y` of the view of `ctx` from scratch.
+pub fn compute_row(ctx: &Ctx, y: usize) -> Vec<PlotPoint> {
+    let coords: Vec<(i64, i64)> = (0..ctx.win_width).map(|x| ctx.get_coords(x, y)).collect();
+    let mut points = vec![PlotPoint::default(); ctx.win_width];
+
+    compute_batches(ctx, &coords, &mut points);
+
+    points
+}
+
+/// Computes the unprocessed points of `chunk` in batches. Points that can resume from an
+/// earlier, lower limit are iterated on their own, as their iterations are out of step.
+pub(crate) fn process_brute_force(ctx: &mut Ctx, chunk: &mut [Vec<PlotPoint>], first_row: usize) {
+    for (y, row) in chunk.iter_mut().enumerate() {
+        let mut coords = Vec::with_capacity(row.len());
+        let mut fresh = Vec::with_capacity(row.len());
+
+        for (x, plot_point) in row.iter_mut().enumerate() {
+            if plot_point.processed() {
+                continue;
+            }
+
+            let (cx, cy) = ctx.get_coords(x, first_row + y);
+            match plot_point.resume_state() {
+                Some((_, i)) if i < ctx.max_iters => {
+                    crate::calculate_point(ctx, plot_point, cx, cy);
+                }
+                _ => {
+                    coords.push((cx, cy));
+                    fresh.push(x);
+                }
+            }
+        }
+
+        let mut points = vec![PlotPoint::default(); fresh.len()];
+        compute_batches(ctx, &coords, &mut points);
+
+        for (x, point) in fresh.into_iter().zip(points) {
+            ctx.include_value(point.calculated_value());
+            row[x] = point;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::Write;
+
+use crate::View;
+
+/// How the iteration limit changes along a zoom path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IterRamp {
+    /// Every frame uses the iteration limit of the start view.
+    Constant,
+    /// The limit moves from the start to the end view's in proportion to the zoom depth.
+    Depth,
+}
+
+/// Returns the view at `t` (from 0 to 1) on the way from `start` to `end`.
+///
+/// The span changes exponentially, so the zoom speed looks constant, and the center moves
+/// so that it reaches `end` exactly when the span does.
+pub fn zoom_view(start: &View, end: &View, t: f64, ramp: IterRamp) -> View {
+    let max_iters = match ramp {
+        IterRamp::Constant => start.max_iters,
+        IterRamp::Depth => {
+            let from = start.max_iters as f64;
+            let to = end.max_iters as f64;
+            (from + (to - from) * t.clamp(0.0, 1.0)).round() as usize
+        }
+    };
+
+    if t <= 0.0 {
+        return View {
+            max_iters,
+            ..*start
+        };
+    }
+    if t >= 1.0 {
+        return View { max_iters, ..*end };
+    }
+
+    let s0 = start.horizontal_span as f64;
+    let s1 = end.horizontal_span as f64;
+    let span = s0 * (s1 / s0).powf(t);
+
+    // Distance still to travel, measured from the end so that deep frames stay exact.
+    let rest = if s0 == s1 {
+        1.0 - t
+    } else {
+        (span - s1) / (s0 - s1)
+    };
+
+    let lerp = |a: i64, b: i64, rest: f64| {
+        (b as i128 - ((b as i128 - a as i128) as f64 * rest) as i128) as i64
+    };
+
+    View {
+        center_x: lerp(start.center_x, end.center_x, rest),
+        center_y: lerp(start.center_y, end.center_y, rest),
+        horizontal_span: span as i64,
+        max_iters,
+    }
+}
+
+/// Returns the view of frame `i` out of `frames` on the way from `start` to `end`.
+pub fn zoom_frame(start: &View, end: &View, frames: usize, i: usize, ramp: IterRamp) -> View {
+    if frames < 2 {
+        return *start;
+    }
+
+    zoom_view(start, end, i as f64 / (frames - 1) as f64, ramp)
+}
+
+pub fn zoom_path(start: &View, end: &View, frames: usize, ramp: IterRamp) -> Vec<View> {
+    (0..frames)
+        .map(|i| zoom_frame(start, end, frames, i, ramp))
+        .collect()
+}
+
+/// Writes the header of an uncompressed 4:4:4 YUV4MPEG2 stream.
+pub fn write_y4m_header<W: Write>(
+    w: &mut W,
+    width: usize,
+    height: usize,
+    fps: usize,
+) -> std::io::Result<()> {
+    writeln!(w, "YUV4MPEG2 W{width} H{height} F{fps}:1 Ip A1:1 C444")
+}
+
+/// Appends one RGBA frame to a YUV4MPEG2 stream, converting it to BT.601 limited range.
+pub fn write_y4m_frame<W: Write>(w: &mut W, rgba: &[u8]) -> std::io::Result<()> {
+    let pixels = rgba.len() / 4;
+    let mut planes = vec![0_u8; pixels * 3];
+
+    for (i, px) in rgba.chunks_exact(4).enumerate() {
+        let (r, g, b) = (px[0] as f64, px[1] as f64, px[2] as f64);
+
+        planes[i] = (16.0 + 0.257 * r + 0.504 * g + 0.098 * b).round() as u8;
+        planes[pixels + i] = (128.0 - 0.148 * r - 0.291 * g + 0.439 * b).round() as u8;
+        planes[2 * pixels + i] = (128.0 + 0.439 * r - 0.368 * g - 0.071 * b).round() as u8;
+    }
+
+    w.write_all(b"FRAME\n")?;
+    w.write_all(&planes)
+}
@@ -0,0 +1,77 @@
+use mandelbrot_web::animation::{Easing, Keyframe, Timeline};
+use mandelbrot_web::{Complex, Ctx, View};
+
+fn keyframe(time: f64, span: i64, palette_offset: f64, easing: Easing) -> Keyframe {
+    Keyframe {
+        time,
+        view: View {
+            horizontal_span: span,
+            ..View::default()
+        },
+        palette_offset,
+        brightness: 0.5,
+        julia: Some(Complex::new(0, (time as i64) << 58)),
+        easing,
+    }
+}
+
+fn timeline(easing: Easing) -> Timeline {
+    let mut timeline = Timeline::default();
+    timeline.add(keyframe(2.0, 1 << 56, 1.0, easing));
+    timeline.add(keyframe(0.0, 1 << 60, 0.0, easing));
+
+    timeline
+}
+
+#[test]
+fn easings_keep_their_endpoints() {
+    for easing in Easing::ALL {
+        assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+        assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+
+        let samples: Vec<f64> = (0..=10).map(|i| easing.apply(i as f64 / 10.0)).collect();
+        assert!(samples.windows(2).all(|w| w[0] <= w[1]), "{easing:?}");
+    }
+
+    assert!(Easing::EaseIn.apply(0.5) < 0.5);
+    assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+}
+
+#[test]
+fn sampling_interpolates_between_keyframes() {
+    let linear = timeline(Easing::Linear);
+    assert_eq!(linear.keyframes()[0].time, 0.0);
+    assert_eq!(linear.duration(), 2.0);
+
+    assert_eq!(linear.sample(-1.0), Some(linear.keyframes()[0]));
+    assert_eq!(linear.sample(3.0), Some(linear.keyframes()[1]));
+
+    let middle = linear.sample(1.0).unwrap();
+    assert_eq!(middle.palette_offset, 0.5);
+    assert_eq!(middle.julia, Some(Complex::new(0, 1 << 58)));
+    // Spans are interpolated geometrically.
+    assert_eq!(middle.view.horizontal_span, 1 << 58);
+
+    let eased = timeline(Easing::EaseIn).sample(1.0).unwrap();
+    assert_eq!(eased.palette_offset, Easing::EaseIn.apply(0.5));
+
+    assert_eq!(Timeline::default().sample(1.0), None);
+}
+
+#[test]
+fn frames_cover_the_whole_timeline() {
+    let ctx = Ctx {
+        win_width: 16,
+        win_height: 9,
+        ..Default::default()
+    };
+    let frames = timeline(Easing::EaseInOut).frames(&ctx, 10.0);
+
+    assert_eq!(frames.len(), 21);
+    assert_eq!(frames[0].view().horizontal_span, 1 << 60);
+    assert_eq!(frames[20].palette_offset, 1.0);
+    assert!(frames.iter().all(|f| f.win_width == 16));
+
+    assert!(timeline(Easing::Linear).frames(&ctx, 0.0).is_empty());
+}
@@ -0,0 +1,90 @@
+use mandelbrot_web::bookmarks::{gallery, Bookmark, Bookmarks};
+use mandelbrot_web::color::default_gradient;
+use mandelbrot_web::{render_plot, Complex, Ctx, View};
+
+mod common;
+
+use common::base_ctx;
+
+#[test]
+fn bookmarks_round_trip_through_json() {
+    let mut ctx = Ctx {
+        palette_offset: 0.3,
+        brightness: 0.4,
+        julia: Some(Complex::new(-(1 << 59), 1 << 58)),
+        ..base_ctx()
+    };
+    ctx.set_view(&View {
+        center_x: 123456789,
+        center_y: -987654321,
+        horizontal_span: 1 << 40,
+        max_iters: 4321,
+    });
+
+    let mut bookmarks = Bookmarks::default();
+    bookmarks.save(Bookmark::from_ctx("Somewhere", &ctx));
+    bookmarks.save(Bookmark::from_ctx("Home", &Ctx::default()));
+
+    let restored = Bookmarks::from_json(&bookmarks.to_json()).unwrap();
+    assert_eq!(restored, bookmarks);
+
+    let mut applied = base_ctx();
+    restored.bookmarks()[0].apply_to(&mut applied);
+    assert_eq!(applied.view(), ctx.view());
+    assert_eq!(applied.palette_offset, 0.3);
+    assert_eq!(applied.brightness, 0.4);
+    assert_eq!(applied.julia, ctx.julia);
+}
+
+#[test]
+fn saving_under_an_existing_name_replaces() {
+    let mut bookmarks = Bookmarks::default();
+    bookmarks.save(Bookmark::from_ctx("A", &Ctx::default()));
+    bookmarks.save(Bookmark::from_ctx("B", &Ctx::default()));
+
+    let ctx = Ctx {
+        palette_offset: 0.5,
+        ..Default::default()
+    };
+    bookmarks.save(Bookmark::from_ctx("A", &ctx));
+
+    let names: Vec<_> = bookmarks.bookmarks().iter().map(|b| &b.name).collect();
+    assert_eq!(names, ["A", "B"]);
+    assert_eq!(bookmarks.bookmarks()[0].palette_offset, 0.5);
+
+    assert!(bookmarks.remove("A").is_some());
+    assert!(bookmarks.remove("A").is_none());
+    assert_eq!(bookmarks.bookmarks().len(), 1);
+}
+
+#[test]
+fn gallery_locations_show_the_boundary() {
+    let gallery = gallery();
+    assert!(gallery.len() >= 5);
+
+    for bookmark in &gallery {
+        let mut ctx = base_ctx().resized(64, 48).unwrap();
+        bookmark.apply_to(&mut ctx);
+
+        let plot = render_plot(&mut ctx);
+        let stable = plot.iter().flatten().filter(|p| p.stable()).count();
+
+        assert!(stable > 0, "{} shows no point of the set", bookmark.name);
+        assert!(
+            stable < 64 * 48 * 3 / 4,
+            "{} is mostly inside",
+            bookmark.name
+        );
+    }
+}
+
+#[test]
+fn thumbnails_have_the_requested_size() {
+    let bookmark = &gallery()[1];
+    let data = bookmark
+        .thumbnail(&Ctx::default(), 40, 30, &default_gradient())
+        .unwrap();
+
+    assert_eq!(data.len(), 4 * 40 * 30);
+    assert!(data.chunks_exact(4).all(|pixel| pixel[3] == 255));
+}
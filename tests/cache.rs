@@ -0,0 +1,104 @@
+use mandelbrot_web::cache::{PlotCache, MAX_CACHED_POINTS};
+use mandelbrot_web::{process_plot_symmetric, render_plot, Ctx, PlotPoint, RenderStrategy};
+
+mod common;
+
+use common::assert_same;
+
+/// A 96x64 window, in exact fixed point so that pans and zooms line up with the pixel grid.
+fn ctx(center_x: i64, center_y: i64, span: i64) -> Ctx {
+    common::fixed_ctx(96, 64, (center_x, center_y), span, 200)
+}
+
+/// Renders `ctx` starting from the points found in `cache`, returning the plot and the number
+/// of points reused.
+fn render_cached(cache: &PlotCache, mut ctx: Ctx) -> (Vec<Vec<PlotPoint>>, usize) {
+    let mut plot = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+
+    ctx.reset_min_max();
+    let reused = cache.fill(&mut ctx, &mut plot);
+    ctx.chunk_size = ctx.win_height;
+    process_plot_symmetric(&mut ctx, &mut plot);
+
+    (plot, reused)
+}
+
+fn assert_matches_brute_force(ctx: Ctx, plot: &[Vec<PlotPoint>]) {
+    let expected = render_plot(&mut Ctx {
+        strategy: RenderStrategy::BruteForce,
+        ..ctx
+    });
+
+    assert_same(&expected, plot, "cached render");
+}
+
+#[test]
+fn exact_zoom_reuses_points() {
+    let (cx, cy) = (-(3 << 58), 1 << 57);
+    let start = ctx(cx, cy, 3 << 60);
+    let mut cache = PlotCache::default();
+    cache.store(
+        &start,
+        &render_plot(&mut Ctx {
+            strategy: RenderStrategy::BruteForce,
+            ..start
+        }),
+    );
+
+    let zoomed = ctx(cx, cy, 3 << 59);
+    let (plot, reused) = render_cached(&cache, zoomed);
+
+    assert_eq!(reused, 96 * 64 / 4);
+    assert_matches_brute_force(zoomed, &plot);
+}
+
+#[test]
+fn integer_pan_reuses_points() {
+    let start = ctx(-(1 << 59), 0, 3 << 60);
+    let mut cache = PlotCache::default();
+    cache.store(&start, &render_plot(&mut { start }));
+
+    let panned = ctx(
+        -(1 << 59) + 7 * start.pixel_size as i64,
+        -3 * start.pixel_size as i64,
+        3 << 60,
+    );
+    let (plot, reused) = render_cached(&cache, panned);
+
+    assert!(reused > 0);
+    assert_matches_brute_force(panned, &plot);
+}
+
+#[test]
+fn other_limits_are_not_reused() {
+    let start = ctx(-(1 << 59), 0, 3 << 60);
+    let mut cache = PlotCache::default();
+    cache.store(&start, &render_plot(&mut { start }));
+
+    let (_, reused) = render_cached(
+        &cache,
+        Ctx {
+            max_iters: 300,
+            ..start
+        },
+    );
+    assert_eq!(reused, 0);
+
+    let (plot, reused) = render_cached(&cache, start);
+    assert!(reused > 0);
+    assert_matches_brute_force(start, &plot);
+}
+
+#[test]
+fn oversized_plots_are_capped() {
+    let big = common::ctx(2500, 1000, (0.0, 0.0), 3.0, 200);
+
+    let mut point = PlotPoint::default();
+    point.calculate(0, 1);
+    let plot = vec![vec![point; big.win_width]; big.win_height];
+
+    let mut cache = PlotCache::default();
+    cache.store(&big, &plot);
+
+    assert_eq!(cache.len(), MAX_CACHED_POINTS);
+}
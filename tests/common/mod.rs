@@ -0,0 +1,70 @@
+//! Helpers shared by the integration tests. Not every test file uses all of them.
+#![allow(dead_code)]
+
+use mandelbrot_web::{Ctx, PlotPoint, View};
+
+/// `v` as a fixed point coordinate.
+pub fn fixed(v: f64) -> i64 {
+    (v * (1_u64 << 60) as f64) as i64
+}
+
+/// A `width` by `height` window showing `span` horizontally around `center`.
+pub fn ctx(width: usize, height: usize, center: (f64, f64), span: f64, max_iters: usize) -> Ctx {
+    let center = (fixed(center.0), fixed(center.1));
+
+    fixed_ctx(width, height, center, fixed(span), max_iters)
+}
+
+/// Same as [`ctx`], for views that need exact fixed point coordinates.
+pub fn fixed_ctx(
+    width: usize,
+    height: usize,
+    center: (i64, i64),
+    span: i64,
+    max_iters: usize,
+) -> Ctx {
+    let mut ctx = Ctx {
+        win_width: width,
+        win_height: height,
+        max_iters,
+        ..Default::default()
+    };
+    ctx.define_bounds(center.0, center.1, span);
+
+    ctx
+}
+
+/// A 640x480 window showing the default view.
+pub fn base_ctx() -> Ctx {
+    let mut ctx = Ctx {
+        win_width: 640,
+        win_height: 480,
+        ..Default::default()
+    };
+    ctx.set_view(&View::default());
+
+    ctx
+}
+
+/// Counts the pixels where the plots hold different values.
+pub fn differing(a: &[Vec<PlotPoint>], b: &[Vec<PlotPoint>]) -> usize {
+    a.iter()
+        .flatten()
+        .zip(b.iter().flatten())
+        .filter(|(a, b)| !a.same_as(b))
+        .count()
+}
+
+/// Asserts that both plots hold the same values, naming `case` and the first pixel that differs.
+pub fn assert_same(expected: &[Vec<PlotPoint>], actual: &[Vec<PlotPoint>], case: &str) {
+    for (y, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        for (x, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+            assert!(
+                actual.same_as(expected),
+                "{case}: pixel ({x}, {y}) differs: expected {:?}, got {:?}",
+                expected.calculated_value(),
+                actual.calculated_value(),
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use mandelbrot_web::export::{
+    decode_view, encode_png, finish_tar, import_png, is_png, view_from_text, write_tar_entry,
+};
+use mandelbrot_web::Ctx;
+
+mod common;
+
+/// A view that only survives a round trip if every bit of it does.
+fn ctx() -> Ctx {
+    common::fixed_ctx(8, 6, (-(3 << 58) + 12345, 1 << 57), 3 << 50, 4321)
+}
+
+#[test]
+fn view_survives_a_png_round_trip() {
+    let ctx = ctx();
+    let png = encode_png(&ctx, &[0; 4 * 8 * 6]).unwrap();
+
+    assert_eq!(decode_view(&png), Some(ctx.view()));
+
+    let mut imported = Ctx {
+        win_width: 8,
+        win_height: 6,
+        ..Default::default()
+    };
+    import_png(&png, &mut imported).unwrap();
+    assert_eq!(imported.view(), ctx.view());
+}
+
+#[test]
+fn images_without_a_view_are_rejected() {
+    assert_eq!(decode_view(b"not a png"), None);
+    assert!(import_png(b"not a png", &mut ctx()).is_err());
+
+    assert_eq!(view_from_text("center_x=1\ncenter_y=2\nmax_iters=3"), None);
+    assert!(is_png("view.PNG"));
+    assert!(!is_png("view.json"));
+}
+
+#[test]
+fn invalid_views_are_not_imported() {
+    let invalid = Ctx {
+        max_iters: 0,
+        ..ctx()
+    };
+    let png = encode_png(&invalid, &[0; 4 * 8 * 6]).unwrap();
+
+    let mut imported = common::base_ctx();
+    let view = imported.view();
+    assert!(import_png(&png, &mut imported).is_err());
+    assert_eq!(imported.view(), view);
+}
+
+#[test]
+fn frames_are_bundled_as_a_tar_archive() {
+    let mut archive = Vec::new();
+    write_tar_entry(&mut archive, "frame_00000.png", &[1; 700]).unwrap();
+    write_tar_entry(&mut archive, "frame_00001.png", &[2; 512]).unwrap();
+    finish_tar(&mut archive).unwrap();
+
+    // A header and two data blocks, a header and one data block, and two empty blocks.
+    assert_eq!(archive.len(), 512 * (3 + 2 + 2));
+
+    let header = &archive[..512];
+    assert_eq!(&header[..15], b"frame_00000.png");
+    assert_eq!(&header[124..136], b"00000001274\0");
+    assert_eq!(&header[257..263], b"ustar\0");
+
+    let stored: u32 = std::str::from_utf8(&header[148..154])
+        .ok()
+        .and_then(|s| u32::from_str_radix(s, 8).ok())
+        .unwrap();
+    let sum: u32 = header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| {
+            if (148..156).contains(&i) {
+                32
+            } else {
+                b as u32
+            }
+        })
+        .sum();
+    assert_eq!(stored, sum);
+
+    assert_eq!(archive[512], 1);
+    assert_eq!(&archive[3 * 512..3 * 512 + 15], b"frame_00001.png");
+    assert!(archive[5 * 512..].iter().all(|&b| b == 0));
+}
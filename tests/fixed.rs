@@ -0,0 +1,55 @@
+use mandelbrot_web::fixed::{from_decimal, to_decimal};
+use proptest::prelude::*;
+
+#[test]
+fn known_values() {
+    assert_eq!(to_decimal(0), "0");
+    assert_eq!(to_decimal(1 << 60), "1");
+    assert_eq!(to_decimal(-(1 << 59)), "-0.5");
+    assert_eq!(to_decimal(3 << 58), "0.75");
+    assert_eq!(
+        to_decimal(1),
+        "0.000000000000000000867361737988403547205962240695953369140625"
+    );
+    assert_eq!(to_decimal(i64::MIN), "-8");
+
+    assert_eq!(from_decimal("1"), Some(1 << 60));
+    assert_eq!(from_decimal("-0.5"), Some(-(1 << 59)));
+    assert_eq!(from_decimal(" +.75 "), Some(3 << 58));
+    assert_eq!(from_decimal("-8"), Some(i64::MIN));
+}
+
+#[test]
+fn invalid_input() {
+    for s in ["", "-", ".", "1.2.3", "abc", "1e5", "8", "-8.5", "12"] {
+        assert_eq!(from_decimal(s), None, "{s:?}");
+    }
+}
+
+#[test]
+fn rounds_to_nearest() {
+    // Half of the smallest step rounds up, less than that rounds down.
+    assert_eq!(
+        from_decimal("0.0000000000000000004336808689942017736029811203479766845703125"),
+        Some(1)
+    );
+    assert_eq!(
+        from_decimal("0.000000000000000000433680868994201773602981120347976684570312"),
+        Some(0)
+    );
+    assert_eq!(from_decimal("0.1"), Some(115292150460684698));
+}
+
+proptest! {
+    #[test]
+    fn decimal_round_trips(v: i64) {
+        prop_assert_eq!(from_decimal(&to_decimal(v)), Some(v));
+    }
+
+    #[test]
+    fn parses_like_f64(v in -7.9_f64..7.9) {
+        let fixed = from_decimal(&v.to_string()).unwrap();
+        let expected = v * (1_u64 << 60) as f64;
+        prop_assert!((fixed as f64 - expected).abs() <= expected.abs() * 1e-15 + 1.0);
+    }
+}
@@ -0,0 +1,122 @@
+use mandelbrot_web::iterations::{
+    change_iters, edge_points, estimate_iters, needs_more_iters, raise_iters, render_plot_auto,
+};
+use mandelbrot_web::{process_plot_symmetric, render_plot, Ctx, RenderStrategy, View};
+
+mod common;
+
+use common::{assert_same, ctx};
+
+#[test]
+fn raising_the_limit_continues_where_it_stopped() {
+    for strategy in [RenderStrategy::BruteForce, RenderStrategy::Tracing] {
+        for symmetry in [true, false] {
+            let mut ctx = Ctx {
+                strategy,
+                symmetry,
+                ..ctx(96, 64, (-0.5, 0.0), 3.0, 20)
+            };
+            let mut plot = render_plot(&mut ctx);
+
+            for _ in 0..3 {
+                raise_iters(&mut ctx, &mut plot);
+                process_plot_symmetric(&mut ctx, &mut plot);
+
+                let expected = render_plot(&mut Ctx {
+                    strategy: RenderStrategy::BruteForce,
+                    ..ctx
+                });
+                assert_same(
+                    &expected,
+                    &plot,
+                    &format!(
+                        "{strategy:?}, symmetry {symmetry}, {} iterations",
+                        ctx.max_iters
+                    ),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn only_points_at_the_limit_are_reopened() {
+    let mut ctx = ctx(96, 64, (-0.745, 0.1), 0.02, 100);
+    let mut plot = render_plot(&mut ctx);
+    let stable = plot.iter().flatten().filter(|p| p.stable()).count();
+
+    raise_iters(&mut ctx, &mut plot);
+
+    let reopened = plot.iter().flatten().filter(|p| !p.processed()).count();
+    assert_eq!(reopened, stable);
+    assert!(plot
+        .iter()
+        .flatten()
+        .all(|p| !p.processed() || p.calculated_value() <= 100));
+    assert!(plot.iter().flatten().all(|p| p.processed() || !p.filled));
+}
+
+#[test]
+fn estimate_grows_with_depth() {
+    let default = View::default();
+    assert_eq!(estimate_iters(&default), default.max_iters);
+
+    let deep = View {
+        horizontal_span: default.horizontal_span >> 40,
+        ..default
+    };
+    assert!(estimate_iters(&deep) > 3 * default.max_iters);
+}
+
+#[test]
+fn auto_iterations_resolve_filaments() {
+    let mut ctx = ctx(96, 64, (-1.7687, 0.0017), 0.0005, 500);
+    let plot = render_plot(&mut ctx);
+    assert!(needs_more_iters(&ctx, &plot));
+
+    let plot = render_plot_auto(&mut ctx);
+    assert!(ctx.max_iters > 1000);
+    assert!(!needs_more_iters(&ctx, &plot));
+}
+
+#[test]
+fn whole_set_keeps_the_estimate() {
+    let mut ctx = ctx(96, 64, (-0.5, 0.0), 3.5, 0);
+    let plot = render_plot_auto(&mut ctx);
+
+    assert_eq!(ctx.max_iters, View::default().max_iters);
+
+    let (edge, undecided) = edge_points(&ctx, &plot);
+    assert!(undecided * 4 < edge);
+}
+
+#[test]
+fn lowering_the_limit_computes_nothing() {
+    for strategy in [RenderStrategy::BruteForce, RenderStrategy::Tracing] {
+        let mut ctx = Ctx {
+            strategy,
+            ..ctx(96, 64, (-0.745, 0.1), 0.02, 400)
+        };
+        let mut plot = render_plot(&mut ctx);
+
+        for max_iters in [150, 40, 300, 1000] {
+            ctx.max_iters = max_iters;
+            change_iters(&mut ctx, &mut plot);
+
+            if max_iters < 400 {
+                assert!(plot.iter().flatten().all(|p| p.processed()));
+            }
+            process_plot_symmetric(&mut ctx, &mut plot);
+
+            let expected = render_plot(&mut Ctx {
+                strategy: RenderStrategy::BruteForce,
+                ..ctx
+            });
+            assert_same(
+                &expected,
+                &plot,
+                &format!("{strategy:?}, {max_iters} iterations"),
+            );
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use mandelbrot_web::{Ctx, SUBPIXEL_BITS};
+use proptest::prelude::*;
+
+mod common;
+
+fn ctx(win_width: usize, win_height: usize, center_x: i64, center_y: i64, span: i64) -> Ctx {
+    common::fixed_ctx(win_width, win_height, (center_x, center_y), span, 100)
+}
+
+prop_compose! {
+    // Pixels at least 2^SUBPIXEL_BITS units wide, so that every sub-pixel position is distinct.
+    fn arb_ctx()(
+        win_width in 1_usize..40000,
+        win_height in 1_usize..40000,
+        center_x in -(1_i64 << 61)..(1_i64 << 61),
+        center_y in -(1_i64 << 61)..(1_i64 << 61),
+        pixel_bits in SUBPIXEL_BITS..44,
+    ) -> Ctx {
+        ctx(win_width, win_height, center_x, center_y, (1_i64 << pixel_bits) * win_width as i64)
+    }
+}
+
+proptest! {
+    #[test]
+    fn x_round_trips(ctx in arb_ctx(), x in -1.0_f64..2.0) {
+        let x = (x * (ctx.win_width << SUBPIXEL_BITS) as f64) as i128;
+        let v = ctx.get_x_sub(x);
+
+        prop_assert_eq!(ctx.revert_x(v), x);
+        prop_assert_eq!(ctx.get_x_sub(ctx.revert_x(v)), v);
+    }
+
+    #[test]
+    fn y_round_trips(ctx in arb_ctx(), y in -1.0_f64..2.0) {
+        let y = (y * (ctx.win_height << SUBPIXEL_BITS) as f64) as i128;
+        let v = ctx.get_y_sub(y);
+
+        prop_assert_eq!(ctx.revert_y(v), y);
+        prop_assert_eq!(ctx.get_y_sub(ctx.revert_y(v)), v);
+    }
+
+    #[test]
+    fn revert_x_finds_nearest_position(ctx in arb_ctx(), v in -(1_i64 << 61)..(1_i64 << 61)) {
+        let x = ctx.revert_x(v);
+
+        prop_assert!(ctx.get_x_sub(x) >= v);
+        prop_assert!(ctx.get_x_sub(x - 1) < v);
+    }
+
+    #[test]
+    fn whole_pixels_match_sub_pixels(ctx in arb_ctx(), x in 0_usize..40000, y in 0_usize..40000) {
+        prop_assert_eq!(ctx.get_x(x), ctx.get_x_sub((x as i128) << SUBPIXEL_BITS));
+        prop_assert_eq!(ctx.get_y(y), ctx.get_y_sub((y as i128) << SUBPIXEL_BITS));
+    }
+}
+
+#[test]
+fn wide_canvas_has_distinct_columns() {
+    let ctx = ctx(16000, 12000, 0, 0, 1 << 62);
+
+    for x in 1..ctx.win_width {
+        assert!(ctx.get_x(x) > ctx.get_x(x - 1));
+    }
+}
+
+#[test]
+fn sub_ctx_shares_pixels() {
+    let ctx = ctx(1000, 700, -(1 << 59), 12345, 3 << 60);
+    let sub = ctx.sub_ctx(256, 512, 256, 188);
+
+    for i in 0..256 {
+        assert_eq!(sub.get_x(i), ctx.get_x(256 + i));
+    }
+    for i in 0..188 {
+        assert_eq!(sub.get_y(i), ctx.get_y(512 + i));
+    }
+}
+
+#[test]
+fn empty_sizes_are_rejected() {
+    let ctx = ctx(640, 480, -(1 << 59), 0, 3 << 60);
+
+    assert!(ctx.resized(0, 480).is_err());
+    assert!(ctx.resized(640, 0).is_err());
+    assert_eq!(ctx.resized(1, 1).unwrap().win_width, 1);
+}
+
+#[test]
+fn resizing_keeps_the_center() {
+    let ctx = ctx(640, 480, -(1 << 59), 12345, 3 << 60);
+
+    // The window grows or shrinks around the center, at the same scale.
+    let mut window = ctx;
+    window.resize(801, 333);
+    assert_eq!(
+        (window.center_x, window.center_y, window.pixel_size),
+        (ctx.center_x, ctx.center_y, ctx.pixel_size)
+    );
+    assert_eq!(window.get_x(400), ctx.get_x(320));
+    assert_eq!(window.get_y(166), ctx.get_y(240));
+
+    // An export shows the same view with more or fewer pixels.
+    let export = ctx.resized(1920, 1440).unwrap();
+    assert_eq!(
+        (export.center_x, export.center_y),
+        (ctx.center_x, ctx.center_y)
+    );
+    assert_eq!(export.pixel_size, ctx.pixel_size / 3);
+    assert_eq!(export.view(), ctx.view());
+}
@@ -0,0 +1,204 @@
+use mandelbrot_web::cache::PlotCache;
+use mandelbrot_web::navigation::{
+    change_max_iters, default_view, key_command, pan, step_max_iters, zoom, History, KeyCommand,
+    MAX_HISTORY, MAX_SPAN, PAN_DIVISOR,
+};
+use mandelbrot_web::{render_plot, Complex, Ctx, PlotPoint, RenderStrategy, View};
+
+mod common;
+
+use common::base_ctx;
+
+#[test]
+fn keys_map_to_commands() {
+    assert_eq!(
+        key_command("ArrowUp", false),
+        Some(KeyCommand::Pan { x: 0, y: 1 })
+    );
+    assert_eq!(key_command("+", false), Some(KeyCommand::ZoomIn));
+    assert_eq!(key_command("=", false), Some(KeyCommand::ZoomIn));
+    assert_eq!(key_command("-", false), Some(KeyCommand::ZoomOut));
+    assert_eq!(key_command("u", false), Some(KeyCommand::Undo));
+    assert_eq!(key_command("z", true), Some(KeyCommand::Undo));
+    assert_eq!(key_command("]", false), Some(KeyCommand::MoreIters));
+    assert_eq!(key_command("?", false), Some(KeyCommand::ToggleHelp));
+
+    // Browser shortcuts keep working.
+    assert_eq!(key_command("s", true), None);
+    assert_eq!(key_command("z", false), None);
+}
+
+#[test]
+fn panning_moves_by_a_fraction_of_the_view() {
+    let ctx = base_ctx();
+
+    let right = pan(&ctx, 1, 0);
+    assert_eq!(
+        right.center_x as i128,
+        ctx.center_x + ctx.pixel_size * (640 / PAN_DIVISOR)
+    );
+    assert_eq!(right.center_y as i128, ctx.center_y);
+    assert_eq!(right.horizontal_span, ctx.view().horizontal_span);
+
+    let down = pan(&ctx, 0, -1);
+    assert_eq!(
+        down.center_y as i128,
+        ctx.center_y - ctx.pixel_size * (480 / PAN_DIVISOR)
+    );
+
+    let mut far = ctx;
+    for _ in 0..1000 {
+        let view = pan(&far, -1, 1);
+        far.set_view(&view);
+    }
+    assert!(far.center_x >= -(4 << 60) && far.center_y <= 4 << 60);
+}
+
+#[test]
+fn panning_keeps_computed_points() {
+    // A size whose span is not a multiple of `PAN_DIVISOR` pixels.
+    let ctx = Ctx {
+        strategy: RenderStrategy::BruteForce,
+        ..common::ctx(100, 70, (-0.8, 0.0), 3.5, 100)
+    };
+
+    let mut cache = PlotCache::default();
+    cache.store(&ctx, &render_plot(&mut { ctx }));
+
+    let mut panned = ctx;
+    panned.set_view(&pan(&ctx, 1, -1));
+    let mut plot = vec![vec![PlotPoint::default(); 100]; 70];
+
+    let (dx, dy) = (100 / PAN_DIVISOR as usize, 70 / PAN_DIVISOR as usize);
+    assert_eq!(cache.fill(&mut panned, &mut plot), (100 - dx) * (70 - dy));
+}
+
+#[test]
+fn zooming_keeps_the_center_and_stays_in_range() {
+    let mut ctx = base_ctx();
+    let view = ctx.view();
+
+    let zoomed_in = zoom(&ctx, true);
+    assert_eq!(zoomed_in.horizontal_span as i128, ctx.pixel_size / 2 * 640);
+    assert_eq!(
+        (zoomed_in.center_x, zoomed_in.center_y),
+        (view.center_x, view.center_y)
+    );
+
+    for _ in 0..100 {
+        let view = zoom(&ctx, true);
+        ctx.set_view(&view);
+    }
+    assert_eq!(ctx.pixel_size, 1);
+
+    for _ in 0..100 {
+        let view = zoom(&ctx, false);
+        ctx.set_view(&view);
+    }
+    assert_eq!(ctx.pixel_size, MAX_SPAN as i128 / 640);
+    assert!(ctx.view().horizontal_span <= MAX_SPAN);
+}
+
+#[test]
+fn zooming_keeps_the_pixel_grid() {
+    let grid = |ctx: &Ctx| {
+        let columns = (0..ctx.win_width).map(|x| ctx.get_x(x));
+        let rows = (0..ctx.win_height).map(|y| ctx.get_y(y));
+
+        [columns.collect::<Vec<_>>(), rows.collect()]
+    };
+
+    for width in [2, 97, 100, 640, 1023] {
+        // A span that isn't a whole number of pixels, as after zooming to a selection.
+        let span = (3 << 48) * width as i64 + width as i64 - 1;
+        let ctx = common::fixed_ctx(width, 30, (-(1 << 59), 1 << 56), span, 100);
+
+        let mut zoomed_in = ctx;
+        zoomed_in.set_view(&zoom(&ctx, true));
+        assert_eq!(zoomed_in.pixel_size * 2, ctx.pixel_size);
+
+        // Every other point of the finer grid is a point of the old one.
+        for (old, new) in grid(&ctx).iter().zip(grid(&zoomed_in)) {
+            let shared = new.iter().filter(|v| old.contains(v)).count();
+            assert!(shared >= new.len() / 2, "width {width}");
+        }
+
+        let mut zoomed_out = ctx;
+        zoomed_out.set_view(&zoom(&ctx, false));
+        assert_eq!(zoomed_out.pixel_size, ctx.pixel_size * 2);
+
+        // Every point of the coarser grid within the old view is a point of the old grid.
+        for (old, new) in grid(&ctx).iter().zip(grid(&zoomed_out)) {
+            let (first, last) = (
+                old[0].min(old[old.len() - 1]),
+                old[0].max(old[old.len() - 1]),
+            );
+            let inside: Vec<_> = new.iter().filter(|v| (first..=last).contains(v)).collect();
+
+            assert!(!inside.is_empty());
+            assert!(inside.iter().all(|v| old.contains(v)), "width {width}");
+        }
+    }
+}
+
+#[test]
+fn resetting_and_iterations() {
+    let mut ctx = base_ctx();
+    assert_eq!(default_view(&ctx), View::default());
+
+    ctx.julia = Some(Complex::new(0, 1 << 60));
+    assert_eq!(default_view(&ctx), View::julia());
+
+    assert_eq!(change_max_iters(500, true), 1000);
+    assert_eq!(change_max_iters(500, false), 250);
+    assert_eq!(change_max_iters(1, false), 1);
+}
+
+#[test]
+fn iteration_keys_request_a_recalculation() {
+    let ctx = Ctx {
+        needs_recalc: false,
+        auto_iters: false,
+        ..base_ctx()
+    };
+
+    let more = step_max_iters(&ctx, true).unwrap();
+    assert_eq!(more.max_iters, 2 * ctx.max_iters);
+    assert!(more.needs_recalc);
+    assert_eq!(more.view().horizontal_span, ctx.view().horizontal_span);
+
+    let fewer = step_max_iters(&ctx, false).unwrap();
+    assert_eq!(fewer.max_iters, ctx.max_iters / 2);
+    assert!(fewer.needs_recalc);
+
+    let auto = Ctx {
+        auto_iters: true,
+        ..ctx
+    };
+    assert_eq!(step_max_iters(&auto, true).map(|ctx| ctx.max_iters), None);
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut history = History::default();
+    let view = |i: i64| View {
+        center_x: i,
+        ..View::default()
+    };
+
+    history.push(view(0));
+    history.push(view(0));
+    assert_eq!(history.len(), 1);
+
+    for i in 1..=MAX_HISTORY as i64 {
+        history.push(view(i));
+    }
+    assert_eq!(history.len(), MAX_HISTORY);
+
+    assert_eq!(history.pop(), Some(view(MAX_HISTORY as i64)));
+    while history.len() > 1 {
+        history.pop();
+    }
+    assert_eq!(history.pop(), Some(view(1)));
+    assert!(history.is_empty());
+}
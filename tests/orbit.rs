@@ -0,0 +1,86 @@
+use mandelbrot_web::orbit::{orbit, orbit_at, MAX_ORBIT_POINTS};
+use mandelbrot_web::{compute_point, Complex, Ctx, Precision};
+
+mod common;
+
+use common::fixed;
+
+fn assert_escapes_match(ctx: &Ctx) {
+    for y in 0..ctx.win_height {
+        for x in 0..ctx.win_width {
+            let point = compute_point(ctx, x, y);
+            let orbit = orbit_at(ctx, x, y);
+
+            if point.stable() {
+                assert_eq!(orbit.escape, None, "{:?}: ({x}, {y})", ctx.precision);
+            } else {
+                assert_eq!(
+                    orbit.escape.map(|i| i as i128),
+                    Some(point.calculated_value()),
+                    "{:?}: ({x}, {y})",
+                    ctx.precision,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn escape_matches_the_renderer() {
+    for precision in Precision::ALL {
+        let ctx = Ctx {
+            precision,
+            ..common::ctx(48, 32, (-0.5, 0.1), 3.0, 100)
+        };
+
+        assert_escapes_match(&ctx);
+    }
+}
+
+#[test]
+fn deep_orbits_use_the_renderer_backend() {
+    // Pixels 2^-54 apart around `i`, where `f64` and Q4.60 results differ.
+    for precision in Precision::ALL {
+        let ctx = Ctx {
+            precision,
+            ..common::fixed_ctx(12, 8, (0, 1 << 60), 12 << 6, 400)
+        };
+
+        assert_escapes_match(&ctx);
+    }
+}
+
+#[test]
+fn orbit_starts_at_z0() {
+    let c = Complex::new(fixed(0.3), fixed(0.5));
+    let orbit = orbit(c, c, 10, Ctx::default().threshold(), Precision::Fixed);
+
+    assert_eq!(orbit.points[0], c);
+    assert_eq!(orbit.points.len(), orbit.iterations + 1);
+}
+
+#[test]
+fn periods_of_known_components() {
+    for (re, im, period) in [
+        (0.0, 0.0, 1),
+        (-0.2, 0.1, 1),
+        (-1.0, 0.0, 2),
+        (-1.1, 0.05, 2),
+        (-0.1226, 0.7449, 3),
+        (-1.3, 0.0, 4),
+    ] {
+        let c = Complex::new(fixed(re), fixed(im));
+        let orbit = orbit(c, c, 5000, Ctx::default().threshold(), Precision::Fixed);
+
+        assert_eq!(orbit.escape, None, "{re} + {im}i");
+        assert_eq!(orbit.period, Some(period), "{re} + {im}i");
+    }
+}
+
+#[test]
+fn long_orbits_are_truncated() {
+    let c = Complex::new(fixed(-0.5), fixed(0.3));
+    let orbit = orbit(c, c, 100_000, Ctx::default().threshold(), Precision::Fixed);
+
+    assert_eq!(orbit.points.len(), MAX_ORBIT_POINTS);
+}
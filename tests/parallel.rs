@@ -0,0 +1,106 @@
+#![cfg(feature = "parallel")]
+
+use mandelbrot_web::parallel::{process_plot_parallel, render_plot_parallel};
+use mandelbrot_web::{
+    process_plot_cpu, process_plot_symmetric, render_plot, Complex, Ctx, PlotPoint, RenderStrategy,
+};
+
+mod common;
+
+use common::fixed;
+
+fn ctx(center: (f64, f64), span: f64, strategy: RenderStrategy, chunk_size: usize) -> Ctx {
+    Ctx {
+        strategy,
+        chunk_size,
+        ..common::ctx(83, 61, center, span, 200)
+    }
+}
+
+/// Whole set, a view off the real axis and a Julia set, in every strategy and chunk size.
+fn views() -> Vec<Ctx> {
+    let mut views = Vec::new();
+
+    for strategy in RenderStrategy::ALL {
+        for chunk_size in [1, 7, 16, 128] {
+            views.push(ctx((-0.5, 0.0), 3.0, strategy, chunk_size));
+            views.push(ctx((-0.75, 0.125), 0.03125, strategy, chunk_size));
+            views.push(Ctx {
+                julia: Some(Complex::new(fixed(-0.75), fixed(0.125))),
+                ..ctx((0.0, 0.0), 3.0, strategy, chunk_size)
+            });
+        }
+    }
+
+    views
+}
+
+/// Besides the values, chunks split across threads must leave the same state to resume from.
+fn assert_identical(actual: &[Vec<PlotPoint>], expected: &[Vec<PlotPoint>], ctx: &Ctx) {
+    let case = format!("{:?}, chunk size {}", ctx.strategy, ctx.chunk_size);
+    common::assert_same(expected, actual, &case);
+
+    for (y, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+        for (x, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!(
+                a.filled == e.filled && a.resume_state() == e.resume_state(),
+                "{case}: state of ({x}, {y}) differs",
+            );
+        }
+    }
+}
+
+#[test]
+fn chunks_match_the_single_threaded_path() {
+    for ctx in views() {
+        let mut sequential_ctx = ctx;
+        let mut sequential = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+        while sequential_ctx.rows_processed < ctx.win_height {
+            process_plot_cpu(&mut sequential_ctx, &mut sequential);
+            sequential_ctx.rows_processed += ctx.chunk_size;
+        }
+
+        let mut parallel_ctx = ctx;
+        let mut parallel = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+        let rows = process_plot_parallel(&mut parallel_ctx, &mut parallel);
+
+        assert_eq!(rows, ctx.win_height);
+        assert_identical(&parallel, &sequential, &ctx);
+        assert_eq!(parallel_ctx.min_value, sequential_ctx.min_value);
+        assert_eq!(parallel_ctx.max_value, sequential_ctx.max_value);
+    }
+}
+
+#[test]
+fn renders_match_chunk_by_chunk_renders() {
+    for ctx in views() {
+        let mut sequential_ctx = ctx;
+        sequential_ctx.reset_min_max();
+        sequential_ctx.step = 1;
+        let mut sequential = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+        while sequential_ctx.rows_processed < sequential_ctx.computed_rows().len() {
+            process_plot_symmetric(&mut sequential_ctx, &mut sequential);
+            sequential_ctx.next_chunk();
+        }
+
+        let mut parallel_ctx = ctx;
+        let parallel = render_plot_parallel(&mut parallel_ctx);
+
+        assert_identical(&parallel, &sequential, &ctx);
+        assert_eq!(parallel_ctx.min_value, sequential_ctx.min_value);
+        assert_eq!(parallel_ctx.max_value, sequential_ctx.max_value);
+    }
+}
+
+#[test]
+fn brute_force_renders_match_whole_renders() {
+    for ctx in views()
+        .into_iter()
+        .filter(|ctx| ctx.strategy == RenderStrategy::BruteForce)
+    {
+        let parallel = render_plot_parallel(&mut { ctx });
+        let whole = render_plot(&mut { ctx });
+
+        assert_identical(&parallel, &whole, &ctx);
+    }
+}
@@ -0,0 +1,131 @@
+use mandelbrot_web::fixed::from_decimal;
+use mandelbrot_web::params::ParamsFormat;
+use mandelbrot_web::{Complex, Ctx, View};
+
+mod common;
+
+use common::base_ctx;
+
+fn seahorse_ctx() -> Ctx {
+    let mut ctx = base_ctx();
+    ctx.set_view(&View {
+        center_x: from_decimal("-0.743643887037158704752191506114774").unwrap(),
+        center_y: from_decimal("0.131825904205311970493132056385139").unwrap(),
+        horizontal_span: from_decimal("0.00000123").unwrap(),
+        max_iters: 3000,
+    });
+    ctx.palette_offset = 0.25;
+    ctx.brightness = 1.5;
+
+    ctx
+}
+
+fn round_trip(format: ParamsFormat, ctx: &Ctx) -> Ctx {
+    let mut imported = base_ctx();
+    format
+        .import(&format.export(ctx), &mut imported)
+        .unwrap_or_else(|e| panic!("{format:?}: {e}"));
+
+    imported
+}
+
+#[test]
+fn json_round_trip_is_exact() {
+    let ctx = Ctx {
+        julia: Some(Complex::new(-(1 << 59) - 12345, 3 << 57)),
+        ..seahorse_ctx()
+    };
+    let imported = round_trip(ParamsFormat::Json, &ctx);
+
+    assert_eq!(imported.view(), ctx.view());
+    assert_eq!(imported.palette_offset, ctx.palette_offset);
+    assert_eq!(imported.brightness, ctx.brightness);
+    assert_eq!(imported.julia, ctx.julia);
+}
+
+#[test]
+fn kfr_round_trip_keeps_the_view() {
+    let ctx = seahorse_ctx();
+    let imported = round_trip(ParamsFormat::Kfr, &ctx);
+    let view = imported.view();
+
+    assert_eq!(view.center_x, ctx.view().center_x);
+    assert_eq!(view.center_y, ctx.view().center_y);
+    assert_eq!(view.max_iters, 3000);
+    assert!((imported.pixel_size - ctx.pixel_size).abs() <= 1);
+    assert_eq!(imported.palette_offset, 0.25);
+}
+
+#[test]
+fn xpf_round_trip_keeps_the_view() {
+    for julia in [None, Some(Complex::new(-(3 << 58), 1 << 56))] {
+        let ctx = Ctx {
+            julia,
+            ..seahorse_ctx()
+        };
+        let imported = round_trip(ParamsFormat::Xpf, &ctx);
+
+        assert_eq!(imported.view(), ctx.view());
+        assert_eq!(imported.julia, julia);
+        assert_eq!(imported.palette_offset, 0.25);
+    }
+}
+
+#[test]
+fn reads_files_of_other_programs() {
+    let kfr = "Re: -0.75\r\nIm: 0.1\r\nZoom: 1E2\r\nIterations: 5000\r\nIterDiv: 1.000000\r\n\
+               ColorOffset: 512\r\nSmooth: 1\r\n";
+    let mut ctx = base_ctx();
+    ParamsFormat::Kfr.import(kfr, &mut ctx).unwrap();
+
+    assert_eq!(ctx.center_x, from_decimal("-0.75").unwrap() as i128);
+    assert_eq!(ctx.center_y, from_decimal("0.1").unwrap() as i128);
+    assert_eq!(ctx.max_iters, 5000);
+    assert_eq!(ctx.palette_offset, 0.5);
+    assert!((ctx.vertical_span - from_decimal("0.04").unwrap() as i128).abs() < 1 << 20);
+
+    let xpf = ";Position file automatically generated by XaoS 4.2.1\n\
+               ;  - a realtime interactive fractal zoomer\n\
+               (initstate)\n(defaultpalette 0)\n(formula 'mandel)\n\
+               (view -1.7549 0 0.035 0.035)\n(maxiter 1000)\n";
+    let mut ctx = base_ctx();
+    ParamsFormat::Xpf.import(xpf, &mut ctx).unwrap();
+
+    assert_eq!(ctx.center_x, from_decimal("-1.7549").unwrap() as i128);
+    assert_eq!(ctx.center_y, 0);
+    assert_eq!(ctx.horizontal_span, from_decimal("0.035").unwrap() as i128);
+    assert_eq!(ctx.max_iters, 1000);
+    assert_eq!(ctx.julia, None);
+}
+
+#[test]
+fn rejects_invalid_files() {
+    let mut ctx = base_ctx();
+    let view = ctx.view();
+
+    assert!(ParamsFormat::Kfr
+        .import("Re: 0\r\nIm: x\r\nZoom: 1\r\n", &mut ctx)
+        .is_err());
+    assert!(ParamsFormat::Xpf.import("(maxiter 100)", &mut ctx).is_err());
+    assert!(ParamsFormat::Xpf
+        .import("(view 0 0 -1 1)", &mut ctx)
+        .is_err());
+    assert!(ParamsFormat::Json.import("{}", &mut ctx).is_err());
+    assert_eq!(ctx.view(), view);
+
+    // Files that parse, but describe a view that can't be rendered.
+    for format in ParamsFormat::ALL {
+        let text = format.export(&Ctx {
+            max_iters: 0,
+            ..seahorse_ctx()
+        });
+        assert!(format.import(&text, &mut ctx).is_err(), "{format:?}");
+        assert_eq!(ctx.view(), view);
+    }
+
+    assert_eq!(
+        ParamsFormat::from_file_name("a.KFR"),
+        Some(ParamsFormat::Kfr)
+    );
+    assert_eq!(ParamsFormat::from_file_name("view"), None);
+}
@@ -0,0 +1,59 @@
+use mandelbrot_web::color::{default_gradient, render_rgba};
+use mandelbrot_web::export::decode_view;
+use mandelbrot_web::poster::{render_poster, TILE_SIZE};
+use mandelbrot_web::Ctx;
+
+mod common;
+
+/// Decodes `png` into its width, height and RGBA data.
+fn decode(png: &[u8]) -> (usize, usize, Vec<u8>) {
+    let mut reader = png::Decoder::new(png).read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    data.truncate(info.buffer_size());
+
+    (info.width as usize, info.height as usize, data)
+}
+
+fn poster(ctx: &Ctx) -> (Vec<u8>, Vec<usize>) {
+    let mut png = Vec::new();
+    let mut progress = Vec::new();
+    render_poster(ctx, &default_gradient(), &mut png, |rows| {
+        progress.push(rows)
+    })
+    .unwrap();
+
+    (png, progress)
+}
+
+#[test]
+fn posters_match_whole_renders() {
+    // Partial tiles at the right and bottom edges, a single partial tile and whole tiles only.
+    for (width, height) in [(300, 257), (100, 40), (2 * TILE_SIZE, TILE_SIZE)] {
+        let ctx = common::ctx(width, height, (-0.75, 0.1), 2.5, 200);
+        let (png, _) = poster(&ctx);
+
+        let (png_width, png_height, data) = decode(&png);
+        assert_eq!((png_width, png_height), (width, height));
+
+        let expected = render_rgba(&mut { ctx }, &default_gradient());
+        for (i, (actual, expected)) in data.chunks(4).zip(expected.chunks(4)).enumerate() {
+            assert_eq!(
+                actual,
+                expected,
+                "{width}x{height}: pixel ({}, {})",
+                i % width,
+                i / width
+            );
+        }
+    }
+}
+
+#[test]
+fn progress_is_reported_per_row_of_tiles() {
+    let ctx = common::ctx(300, 257, (-0.75, 0.1), 2.5, 200);
+    let (png, progress) = poster(&ctx);
+
+    assert_eq!(progress, [TILE_SIZE, 257]);
+    assert_eq!(decode_view(&png), Some(ctx.view()));
+}
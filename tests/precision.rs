@@ -0,0 +1,90 @@
+use mandelbrot_web::iterations::raise_iters;
+use mandelbrot_web::precision::{MIN_FIXED_PIXEL_SIZE, MIN_FLOAT_PIXEL_SIZE};
+use mandelbrot_web::{process_plot_symmetric, render_plot, Ctx, PlotPoint, Precision};
+
+mod common;
+
+use common::{assert_same, differing};
+
+fn shallow_ctx(precision: Precision) -> Ctx {
+    Ctx {
+        precision,
+        ..common::ctx(96, 64, (-0.5, 0.0), 3.0, 200)
+    }
+}
+
+/// A view around `i` with pixels 2^-54 apart, too close for `f64` to tell apart. The orbit of
+/// `i` lands on a repelling cycle, so points near it escape after a few dozen iterations.
+fn deep_ctx(precision: Precision) -> Ctx {
+    Ctx {
+        precision,
+        ..common::fixed_ctx(24, 16, (0, 1 << 60), 24 << 6, 400)
+    }
+}
+
+#[test]
+fn auto_picks_the_cheapest_accurate_backend() {
+    assert_eq!(
+        Precision::for_pixel_size(MIN_FLOAT_PIXEL_SIZE),
+        Precision::Float
+    );
+    assert_eq!(
+        Precision::for_pixel_size(MIN_FLOAT_PIXEL_SIZE - 1),
+        Precision::Fixed
+    );
+    assert_eq!(
+        Precision::for_pixel_size(MIN_FIXED_PIXEL_SIZE - 1),
+        Precision::Arbitrary
+    );
+
+    assert_eq!(shallow_ctx(Precision::Auto).backend(), Precision::Float);
+    assert_eq!(deep_ctx(Precision::Auto).backend(), Precision::Arbitrary);
+    assert_eq!(deep_ctx(Precision::Fixed).backend(), Precision::Fixed);
+}
+
+#[test]
+fn backends_agree_at_shallow_zoom() {
+    let fixed = render_plot(&mut shallow_ctx(Precision::Fixed));
+
+    for precision in [Precision::Float, Precision::Arbitrary] {
+        let plot = render_plot(&mut shallow_ctx(precision));
+        let differing = differing(&fixed, &plot);
+
+        assert!(differing * 100 < 96 * 64, "{precision:?}: {differing}");
+    }
+}
+
+#[test]
+fn arbitrary_precision_resolves_deep_views() {
+    let arbitrary = render_plot(&mut deep_ctx(Precision::Arbitrary));
+    let float = render_plot(&mut deep_ctx(Precision::Float));
+
+    // Rows around `im = 1` round to the same f64 in groups of four, so its plot repeats them.
+    let repeated_rows = |plot: &[Vec<PlotPoint>]| {
+        plot.windows(2)
+            .filter(|rows| rows[0].iter().zip(&rows[1]).all(|(a, b)| a.same_as(b)))
+            .count()
+    };
+    assert!(repeated_rows(&arbitrary) * 3 < repeated_rows(&float));
+    assert!(differing(&arbitrary, &float) > 0);
+}
+
+#[test]
+fn every_backend_resumes_and_mirrors_exactly() {
+    for precision in [Precision::Float, Precision::Fixed, Precision::Arbitrary] {
+        let mut ctx = Ctx {
+            max_iters: 20,
+            ..shallow_ctx(precision)
+        };
+        let mut plot = render_plot(&mut ctx);
+
+        raise_iters(&mut ctx, &mut plot);
+        process_plot_symmetric(&mut ctx, &mut plot);
+
+        let expected = render_plot(&mut Ctx {
+            symmetry: false,
+            ..ctx
+        });
+        assert_same(&expected, &plot, &format!("{precision:?}"));
+    }
+}
@@ -0,0 +1,95 @@
+use mandelbrot_web::color::{colorize, default_gradient};
+use mandelbrot_web::{process_plot_progressive, render_plot, Ctx, PlotPoint, RenderStrategy};
+
+mod common;
+
+use common::assert_same;
+
+fn ctx(width: usize, height: usize, strategy: RenderStrategy) -> Ctx {
+    let mut ctx = Ctx {
+        strategy,
+        chunk_size: 16,
+        ..common::ctx(width, height, (-0.5, 0.0625), 3.0, 200)
+    };
+    ctx.reset_min_max();
+    ctx.start_render();
+
+    ctx
+}
+
+fn step(ctx: &mut Ctx, plot: &mut [Vec<PlotPoint>]) {
+    ctx.total_rows_processed += process_plot_progressive(ctx, plot);
+    ctx.next_chunk();
+}
+
+#[test]
+fn coarse_passes_compute_every_step_th_point() {
+    let mut ctx = ctx(61, 45, RenderStrategy::Tracing);
+    let mut plot = vec![vec![PlotPoint::default(); 61]; 45];
+
+    for pass_step in [8, 4, 2] {
+        assert_eq!(ctx.step, pass_step);
+
+        while ctx.step == pass_step {
+            step(&mut ctx, &mut plot);
+        }
+
+        for (y, row) in plot.iter().enumerate() {
+            for (x, point) in row.iter().enumerate() {
+                let on_grid = x % pass_step == 0 && y % pass_step == 0;
+                assert_eq!(point.processed(), on_grid, "step {pass_step}, ({x}, {y})");
+            }
+        }
+    }
+
+    assert_eq!(ctx.total_rows_processed, 0);
+}
+
+#[test]
+fn coarse_passes_take_about_one_chunk() {
+    // As many rows as 8 chunks of the final pass.
+    let mut ctx = ctx(61, 128, RenderStrategy::Tracing);
+    let mut plot = vec![vec![PlotPoint::default(); 61]; 128];
+
+    for (pass_step, chunks) in [(8, 1), (4, 1), (2, 2)] {
+        let mut taken = 0;
+        while ctx.step == pass_step {
+            step(&mut ctx, &mut plot);
+            taken += 1;
+        }
+        assert_eq!(taken, chunks, "step {pass_step}");
+    }
+}
+
+#[test]
+fn progressive_render_matches_direct_one() {
+    for strategy in [RenderStrategy::BruteForce, RenderStrategy::Tracing] {
+        let mut ctx = ctx(96, 64, strategy);
+        let expected = render_plot(&mut Ctx {
+            strategy: RenderStrategy::BruteForce,
+            ..ctx
+        });
+
+        let mut plot = vec![vec![PlotPoint::default(); 96]; 64];
+        while ctx.total_rows_processed < ctx.win_height {
+            step(&mut ctx, &mut plot);
+        }
+
+        assert_same(&expected, &plot, &format!("{strategy:?}"));
+    }
+}
+
+#[test]
+fn first_pass_colors_the_whole_picture() {
+    let mut ctx = ctx(61, 45, RenderStrategy::Tracing);
+    let mut plot = vec![vec![PlotPoint::default(); 61]; 45];
+
+    while ctx.step == 8 {
+        step(&mut ctx, &mut plot);
+    }
+
+    let mut data = vec![0_u8; 4 * 61 * 45];
+    colorize(&ctx, &default_gradient(), &plot, &mut data);
+
+    assert!(data.chunks_exact(4).all(|pixel| pixel[3] == 255));
+}
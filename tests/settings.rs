@@ -0,0 +1,76 @@
+use mandelbrot_web::settings::{Settings, SETTINGS_VERSION};
+use mandelbrot_web::{Complex, Precision, RenderStrategy, View};
+
+mod common;
+
+use common::base_ctx;
+
+fn changed_settings() -> Settings {
+    Settings {
+        view: View {
+            center_x: -(3 << 58),
+            center_y: 1 << 56,
+            horizontal_span: 1 << 50,
+            max_iters: 2500,
+        },
+        julia: Some(Complex::new(1 << 57, -(1 << 58))),
+        auto_iters: true,
+        brightness: 1.2,
+        palette_offset: 0.3,
+        chunk_size: 32,
+        strategy: RenderStrategy::Subdivision,
+        progressive: false,
+        symmetry: false,
+        bailout: 2.25,
+        precision: Precision::Fixed,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn settings_survive_a_reload() {
+    let settings = changed_settings();
+    let restored = Settings::from_json(&settings.to_json()).unwrap();
+    assert_eq!(restored, settings);
+
+    let mut ctx = base_ctx();
+    restored.apply_to(&mut ctx);
+    assert_eq!(Settings::from_ctx(&ctx), settings);
+    assert_eq!(ctx.win_width, 640);
+}
+
+#[test]
+fn defaults_match_a_fresh_ctx() {
+    let settings = Settings::default();
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert_eq!(settings.view, View::default());
+
+    let mut ctx = base_ctx();
+    changed_settings().apply_to(&mut ctx);
+    Settings::default().apply_to(&mut ctx);
+    assert_eq!(Settings::from_ctx(&ctx), Settings::from_ctx(&base_ctx()));
+}
+
+#[test]
+fn other_versions_and_invalid_settings_are_ignored() {
+    let other_version = Settings {
+        version: SETTINGS_VERSION + 1,
+        ..changed_settings()
+    };
+    assert_eq!(Settings::from_json(&other_version.to_json()), None);
+
+    let no_chunks = Settings {
+        chunk_size: 0,
+        ..changed_settings()
+    };
+    assert_eq!(Settings::from_json(&no_chunks.to_json()), None);
+
+    let small_bailout = Settings {
+        bailout: 1.0,
+        ..changed_settings()
+    };
+    assert_eq!(Settings::from_json(&small_bailout.to_json()), None);
+
+    assert_eq!(Settings::from_json("{\"version\": 1}"), None);
+    assert_eq!(Settings::from_json("not json"), None);
+}
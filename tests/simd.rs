@@ -0,0 +1,74 @@
+#![cfg(feature = "simd")]
+
+use mandelbrot_web::iterations::raise_iters;
+use mandelbrot_web::simd::compute_row;
+use mandelbrot_web::{
+    compute_point, process_plot_symmetric, render_plot, Complex, Ctx, Precision, RenderStrategy,
+};
+
+mod common;
+
+use common::{assert_same, fixed};
+
+fn ctx(center: (f64, f64), span: f64) -> Ctx {
+    Ctx {
+        precision: Precision::Float,
+        strategy: RenderStrategy::BruteForce,
+        ..common::ctx(97, 64, center, span, 300)
+    }
+}
+
+fn views() -> Vec<Ctx> {
+    vec![
+        ctx((-0.5, 0.0), 3.0),
+        ctx((-0.75, 0.125), 0.03125),
+        Ctx {
+            julia: Some(Complex::new(fixed(-0.75), fixed(0.125))),
+            ..ctx((0.0, 0.0), 3.0)
+        },
+    ]
+}
+
+#[test]
+fn kernel_matches_the_scalar_iteration() {
+    for ctx in views() {
+        for y in [0, 17, 63] {
+            let row = compute_row(&ctx, y);
+
+            for (x, point) in row.iter().enumerate() {
+                assert!(point.same_as(&compute_point(&ctx, x, y)), "({x}, {y})");
+            }
+        }
+    }
+}
+
+#[test]
+fn brute_force_renders_match_tracing() {
+    for ctx in views() {
+        let simd = render_plot(&mut ctx.clone());
+        let tracing = render_plot(&mut Ctx {
+            strategy: RenderStrategy::Tracing,
+            ..ctx
+        });
+
+        assert_same(&tracing, &simd, "SIMD brute force");
+    }
+}
+
+#[test]
+fn raised_limits_resume_outside_of_batches() {
+    let mut ctx = Ctx {
+        max_iters: 20,
+        ..views()[0]
+    };
+    let mut plot = render_plot(&mut ctx);
+
+    raise_iters(&mut ctx, &mut plot);
+    process_plot_symmetric(&mut ctx, &mut plot);
+
+    for (y, row) in plot.iter().enumerate() {
+        for (x, point) in row.iter().enumerate() {
+            assert!(point.same_as(&compute_point(&ctx, x, y)), "({x}, {y})");
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use mandelbrot_web::{compute_point, render_plot, Ctx, RealAxis};
+
+mod common;
+
+use common::fixed_ctx;
+
+/// Views with the axis on a row, between rows, off the pixel grid and outside the plot.
+fn views(height: usize) -> Vec<Ctx> {
+    let pixel_size = 3 << 54;
+    let span = pixel_size * 40;
+
+    let view = |center_y| fixed_ctx(40, height, (-(1 << 59), center_y), span, 100);
+
+    vec![
+        view(0),
+        view(-pixel_size / 2),
+        view(pixel_size * 3),
+        view(pixel_size / 2 + pixel_size * 5),
+        view(12345),
+        view(-pixel_size * height as i64),
+    ]
+}
+
+#[test]
+fn mirror_rows_have_negated_coordinates() {
+    for height in 1..=32 {
+        for ctx in views(height) {
+            for y in 0..height {
+                let expected = (0..height).find(|&m| ctx.get_y(m) == -ctx.get_y(y));
+                assert_eq!(ctx.mirror_row(y), expected, "height {height}, row {y}");
+            }
+        }
+    }
+}
+
+#[test]
+fn real_axis_position() {
+    for height in 2..=32 {
+        let views = views(height);
+
+        match views[0].real_axis() {
+            RealAxis::Row(y) => assert_eq!(views[0].get_y(y), 0),
+            other => panic!("Expected the axis on a row for height {height}, got {other:?}"),
+        }
+
+        match views[1].real_axis() {
+            RealAxis::BetweenRows(y) => assert_eq!(views[1].get_y(y), -views[1].get_y(y + 1)),
+            other => panic!("Expected the axis between rows for height {height}, got {other:?}"),
+        }
+
+        match views[2].real_axis() {
+            RealAxis::Row(y) => assert_eq!(views[2].get_y(y), 0),
+            RealAxis::None => assert!(height / 2 + 3 >= height),
+            other => panic!("Expected the axis on a row for height {height}, got {other:?}"),
+        }
+
+        assert_eq!(views[4].real_axis(), RealAxis::None);
+        assert_eq!(views[5].real_axis(), RealAxis::None);
+    }
+}
+
+#[test]
+fn computed_rows_cover_the_plot() {
+    for height in 1..=32 {
+        for ctx in views(height) {
+            let rows = ctx.computed_rows();
+
+            for y in (0..rows.start).chain(rows.end..height) {
+                let mirror = ctx
+                    .mirror_row(y)
+                    .expect("Skipped rows should have a mirror.");
+                assert!(rows.contains(&mirror), "height {height}, row {y}");
+            }
+        }
+    }
+}
+
+#[test]
+fn no_mirroring_when_disabled_or_for_complex_julia_sets() {
+    let mut ctx = views(21)[0];
+    ctx.symmetry = false;
+    assert_eq!(ctx.computed_rows(), 0..21);
+
+    let mut ctx = views(21)[0];
+    ctx.julia = Some(mandelbrot_web::Complex::new(0, 1 << 58));
+    assert_eq!(ctx.computed_rows(), 0..21);
+}
+
+#[test]
+fn mirrored_rows_match_computed_ones() {
+    for height in [1, 2, 3, 16, 17, 40, 41] {
+        for mut ctx in views(height) {
+            let rows = ctx.computed_rows();
+            let plot = render_plot(&mut ctx);
+
+            for y in (0..rows.start).chain(rows.end..height) {
+                for (x, point) in plot[y].iter().enumerate() {
+                    if point.filled {
+                        continue;
+                    }
+
+                    let expected = compute_point(&ctx, x, y);
+                    assert_eq!(point.stable(), expected.stable());
+                    assert_eq!(point.calculated_value(), expected.calculated_value());
+                }
+            }
+        }
+    }
+}
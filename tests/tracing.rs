@@ -0,0 +1,98 @@
+use mandelbrot_web::{process_plot_symmetric, Complex, Ctx, PlotPoint, RenderStrategy};
+
+mod common;
+
+use common::{assert_same, ctx, differing, fixed};
+
+/// The whole set, Seahorse Valley, a deep zoom next to a mini-brot, a view with the real axis
+/// off center and a Julia set.
+fn views(width: usize, height: usize) -> Vec<Ctx> {
+    let mut julia = ctx(width, height, (0.0, 0.0), 3.0, 200);
+    julia.julia = Some(Complex::new(fixed(-0.8), fixed(0.156)));
+
+    vec![
+        ctx(width, height, (-0.5, 0.0), 3.5, 200),
+        ctx(width, height, (-0.745, 0.1), 0.02, 500),
+        ctx(width, height, (-1.7687, 0.0017), 0.0005, 1000),
+        ctx(width, height, (-0.2, 0.3), 2.0, 100),
+        julia,
+    ]
+}
+
+/// Renders `ctx` chunk by chunk, the way the worker does.
+fn render(mut ctx: Ctx, strategy: RenderStrategy, chunk_size: usize) -> Vec<Vec<PlotPoint>> {
+    let mut plot = vec![vec![PlotPoint::default(); ctx.win_width]; ctx.win_height];
+
+    ctx.strategy = strategy;
+    ctx.chunk_size = chunk_size;
+    ctx.reset_min_max();
+
+    while ctx.total_rows_processed < ctx.win_height {
+        ctx.total_rows_processed += process_plot_symmetric(&mut ctx, &mut plot);
+        ctx.rows_processed += ctx.chunk_size;
+    }
+
+    plot
+}
+
+#[test]
+fn tracing_matches_brute_force() {
+    for (width, height) in [(96, 64), (61, 47), (1, 9), (9, 1)] {
+        for (i, view) in views(width, height).into_iter().enumerate() {
+            let expected = render(view, RenderStrategy::BruteForce, height);
+
+            for chunk_size in [1, 7, 16, height] {
+                for symmetry in [true, false] {
+                    let view = Ctx { symmetry, ..view };
+                    let actual = render(view, RenderStrategy::Tracing, chunk_size);
+
+                    assert_same(
+                        &expected,
+                        &actual,
+                        &format!(
+                            "{width}x{height}, view {i}, chunk {chunk_size}, symmetry {symmetry}"
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn tracing_computes_fewer_points() {
+    let view = views(200, 150).remove(0);
+    let plot = render(view, RenderStrategy::Tracing, 150);
+
+    let filled = plot.iter().flatten().filter(|p| p.filled).count();
+    assert!(filled > 200 * 150 / 4, "Only {filled} points were filled");
+}
+
+#[test]
+fn every_point_is_processed() {
+    for view in views(50, 30) {
+        for strategy in [RenderStrategy::BruteForce, RenderStrategy::Tracing] {
+            let plot = render(view, strategy, 8);
+            assert!(plot.iter().flatten().all(|p| p.processed()));
+        }
+    }
+}
+
+#[test]
+fn subdivision_is_close_to_brute_force() {
+    for (i, view) in views(96, 64).into_iter().enumerate() {
+        let expected = render(view, RenderStrategy::BruteForce, 64);
+
+        for chunk_size in [16, 64] {
+            let actual = render(view, RenderStrategy::Subdivision, chunk_size);
+
+            assert!(actual.iter().flatten().all(|p| p.processed()));
+
+            let differing = differing(&actual, &expected);
+            assert!(
+                differing * 100 <= 96 * 64,
+                "View {i}, chunk {chunk_size}: {differing} pixels differ"
+            );
+        }
+    }
+}
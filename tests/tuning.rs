@@ -0,0 +1,75 @@
+use mandelbrot_web::cache::PlotCache;
+use mandelbrot_web::orbit::orbit;
+use mandelbrot_web::{
+    render_plot, Complex, Ctx, Precision, RenderStrategy, MAX_BAILOUT, MAX_CHUNK_SIZE,
+};
+
+mod common;
+
+use common::fixed;
+
+fn ctx(bailout: f64) -> Ctx {
+    Ctx {
+        bailout,
+        ..common::ctx(96, 64, (-0.5, 0.0), 3.0, 200)
+    }
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    assert!(ctx(2.0).validate().is_ok());
+    assert!(ctx(MAX_BAILOUT).validate().is_ok());
+    assert!(ctx(1.9).validate().is_err());
+    assert!(ctx(MAX_BAILOUT + 0.1).validate().is_err());
+    assert!(ctx(f64::NAN).validate().is_err());
+
+    for chunk_size in [0, MAX_CHUNK_SIZE + 1] {
+        assert!(Ctx {
+            chunk_size,
+            ..ctx(2.0)
+        }
+        .validate()
+        .is_err());
+    }
+}
+
+#[test]
+fn bailout_keeps_the_set_and_delays_escapes() {
+    for strategy in [RenderStrategy::BruteForce, RenderStrategy::Tracing] {
+        let default = render_plot(&mut Ctx {
+            strategy,
+            ..ctx(2.0)
+        });
+        let larger = render_plot(&mut Ctx {
+            strategy,
+            ..ctx(MAX_BAILOUT)
+        });
+
+        for (d, l) in default.iter().flatten().zip(larger.iter().flatten()) {
+            assert_eq!(d.stable(), l.stable(), "{strategy:?}");
+            assert!(l.calculated_value() >= d.calculated_value(), "{strategy:?}");
+        }
+    }
+
+    let c = Complex::new(fixed(0.3), fixed(0.6));
+    let default = orbit(c, c, 100, ctx(2.0).threshold(), Precision::Fixed);
+    let larger = orbit(c, c, 100, ctx(MAX_BAILOUT).threshold(), Precision::Fixed);
+    assert!(larger.escape.unwrap() >= default.escape.unwrap());
+}
+
+#[test]
+fn cache_is_not_reused_across_bailouts() {
+    let mut default = ctx(2.0);
+    let plot = render_plot(&mut default);
+
+    let mut cache = PlotCache::default();
+    cache.store(&default, &plot);
+
+    let mut larger = ctx(MAX_BAILOUT);
+    let mut plot = render_plot(&mut ctx(MAX_BAILOUT));
+    plot.iter_mut().flatten().for_each(|p| p.reset());
+    assert_eq!(cache.fill(&mut larger, &mut plot), 0);
+    assert!(cache.fill(&mut default, &mut plot) > 0);
+
+    assert!(!default.same_points(&larger));
+}
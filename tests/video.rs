@@ -0,0 +1,75 @@
+use mandelbrot_web::video::{
+    write_y4m_frame, write_y4m_header, zoom_frame, zoom_path, zoom_view, IterRamp,
+};
+use mandelbrot_web::View;
+
+mod common;
+
+use common::fixed;
+
+fn start() -> View {
+    View::default()
+}
+
+fn end() -> View {
+    View {
+        center_x: fixed(-0.7463),
+        center_y: fixed(0.1102),
+        horizontal_span: 1 << 30,
+        max_iters: 5000,
+    }
+}
+
+#[test]
+fn paths_start_and_end_at_the_given_views() {
+    let path = zoom_path(&start(), &end(), 50, IterRamp::Depth);
+
+    assert_eq!(path.len(), 50);
+    assert_eq!(path[0], start());
+    assert_eq!(path[49], end());
+
+    assert_eq!(zoom_frame(&start(), &end(), 1, 0, IterRamp::Depth), start());
+    assert_eq!(zoom_view(&start(), &end(), 2.0, IterRamp::Depth), end());
+
+    // Whatever the ramp, the last frame is centered on the target at the end span.
+    let last = zoom_path(&start(), &end(), 50, IterRamp::Constant)[49];
+    assert_eq!(
+        (last.center_x, last.center_y),
+        (end().center_x, end().center_y)
+    );
+    assert_eq!(last.horizontal_span, end().horizontal_span);
+}
+
+#[test]
+fn span_shrinks_steadily() {
+    let path = zoom_path(&start(), &end(), 100, IterRamp::Depth);
+
+    for pair in path.windows(2) {
+        assert!(pair[1].horizontal_span < pair[0].horizontal_span);
+        assert!(pair[1].max_iters >= pair[0].max_iters);
+    }
+
+    // Each frame zooms in by the same factor.
+    let ratio = |i: usize| path[i].horizontal_span as f64 / path[i + 1].horizontal_span as f64;
+    assert!((ratio(0) / ratio(90) - 1.0).abs() < 1e-3);
+
+    let constant = zoom_path(&start(), &end(), 10, IterRamp::Constant);
+    assert!(constant.iter().all(|v| v.max_iters == start().max_iters));
+}
+
+#[test]
+fn y4m_header_and_frames_have_the_expected_size() {
+    let mut header = Vec::new();
+    write_y4m_header(&mut header, 8, 6, 25).unwrap();
+    assert_eq!(header, b"YUV4MPEG2 W8 H6 F25:1 Ip A1:1 C444\n");
+
+    let rgba: Vec<u8> = [255, 255, 255, 255].repeat(8 * 6);
+    let mut frame = Vec::new();
+    write_y4m_frame(&mut frame, &rgba).unwrap();
+
+    assert_eq!(&frame[..6], b"FRAME\n");
+    assert_eq!(frame.len(), 6 + 3 * 8 * 6);
+    // White is at the top of the limited luma range, with neutral chroma.
+    assert_eq!(frame[6], 235);
+    assert_eq!(frame[6 + 8 * 6], 128);
+}